@@ -0,0 +1,215 @@
+#![forbid(unsafe_code)]
+#![deny(missing_docs)]
+
+//! # Iron Sea - Index Derive
+//!
+//! Derive macros for the traits of the Iron Sea database toolkit
+//! indices.
+//!
+//! These are re-exported by `ironsea_index` when its `derive` feature
+//! is enabled, and should be used through it.
+
+extern crate proc_macro;
+
+mod parse;
+mod record;
+mod record_fields;
+
+use proc_macro::TokenStream;
+
+use parse::Input;
+
+/// Derive `ironsea_index::Record` for each field marked with `#[key]`.
+///
+/// One implementation is generated per marked field, returning a clone
+/// of that field. As a type cannot implement `Record<K>` twice for the
+/// same `K`, marking two fields of the same type is an error.
+///
+/// On an `enum`, `Record` is implemented with its discriminant as the
+/// key, typed after the integer given to `#[repr]`, or `isize` by
+/// default. Discriminants which are not given explicitly depend on the
+/// position of the variants, so reordering them changes the keys of the
+/// records: indices built before the change have to be rebuilt.
+///
+/// Generic items are not supported.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Record;
+///
+/// #[derive(Record)]
+/// struct Foo {
+///     #[key]
+///     id: u64,
+///     #[key]
+///     name: String,
+///     _age: u8,
+/// }
+///
+/// let foo = Foo { id: 42, name: "foo".to_string(), _age: 7 };
+///
+/// assert_eq!(Record::<u64>::key(&foo), 42);
+/// assert_eq!(Record::<String>::key(&foo), "foo");
+/// ```
+///
+/// The same applies to tuple structs:
+///
+/// ```
+/// use ironsea_index::Record;
+///
+/// #[derive(Record)]
+/// struct Bar(#[key] i32, f64);
+///
+/// assert_eq!(Bar(-1, 0.5).key(), -1);
+/// ```
+///
+/// Indexing an `enum` by variant:
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq, Record)]
+/// #[repr(u8)]
+/// enum Event {
+///     Start { at: u64 },
+///     Stop(u64),
+///     Reset = 7,
+///     Pause,
+/// }
+///
+/// assert_eq!(Event::Stop(3).key(), 1);
+/// assert_eq!(Event::Pause.key(), 8);
+///
+/// let index = SortedVecIndex::new(vec![
+///     Event::Stop(2),
+///     Event::Start { at: 0 },
+///     Event::Reset,
+///     Event::Stop(5),
+/// ]);
+///
+/// let stops = index.find(&Event::Stop(0).key()).collect::<Vec<_>>();
+/// assert_eq!(stops, vec![&Event::Stop(2), &Event::Stop(5)]);
+/// ```
+///
+/// Two keys cannot share the same type:
+///
+/// ```compile_fail
+/// use ironsea_index::Record;
+///
+/// #[derive(Record)]
+/// struct Foo {
+///     #[key]
+///     a: u64,
+///     #[key]
+///     b: u64,
+/// }
+/// ```
+///
+/// At least one field has to be marked:
+///
+/// ```compile_fail
+/// use ironsea_index::Record;
+///
+/// #[derive(Record)]
+/// struct Foo {
+///     a: u64,
+/// }
+/// ```
+#[proc_macro_derive(Record, attributes(key))]
+pub fn derive_record(input: TokenStream) -> TokenStream {
+    Input::parse(input)
+        .and_then(|input| record::expand(&input))
+        .unwrap_or_else(parse::Error::into_compile_error)
+}
+
+/// Derive `ironsea_index::RecordFields`, returning all the fields which
+/// are not marked with `#[key]`.
+///
+/// By default the fields are returned as a tuple, in declaration order.
+/// With `#[fields(struct = "Name")]`, a struct `Name` holding these
+/// fields is generated instead, with the same visibility as the item.
+/// Traits can be derived on the generated struct with
+/// `#[fields(struct = "Name", derive(...))]`.
+///
+/// The fields are cloned, and generic items are not supported.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Record;
+/// use ironsea_index::RecordFields;
+///
+/// #[derive(Record, RecordFields)]
+/// struct Measurement {
+///     #[key]
+///     t: i64,
+///     temp: f64,
+///     humidity: f64,
+/// }
+///
+/// let m = Measurement { t: 1, temp: 21.5, humidity: 0.4 };
+///
+/// assert_eq!(m.key(), 1);
+/// assert_eq!(m.fields(), (21.5, 0.4));
+/// ```
+///
+/// Generating a struct for the fields:
+///
+/// ```
+/// use ironsea_index::Record;
+/// use ironsea_index::RecordFields;
+///
+/// #[derive(Record, RecordFields)]
+/// #[fields(struct = "MeasurementFields", derive(Debug, PartialEq))]
+/// struct Measurement {
+///     #[key]
+///     t: i64,
+///     temp: f64,
+///     humidity: f64,
+/// }
+///
+/// let m = Measurement { t: 1, temp: 21.5, humidity: 0.4 };
+///
+/// assert_eq!(m.fields(), MeasurementFields { temp: 21.5, humidity: 0.4 });
+/// ```
+///
+/// When every field is a key, the fields are empty:
+///
+/// ```
+/// use ironsea_index::Record;
+/// use ironsea_index::RecordFields;
+///
+/// #[derive(Record, RecordFields)]
+/// struct Tuple(#[key] i64);
+///
+/// #[derive(Record, RecordFields)]
+/// #[fields(struct = "NamedFields", derive(Debug, PartialEq))]
+/// struct Named {
+///     #[key]
+///     t: i64,
+/// }
+///
+/// assert_eq!(Tuple(1).fields(), ());
+/// assert_eq!(Named { t: 1 }.fields(), NamedFields {});
+/// ```
+///
+/// The name of the generated struct is required:
+///
+/// ```compile_fail
+/// use ironsea_index::RecordFields;
+///
+/// #[derive(RecordFields)]
+/// #[fields(derive(Debug))]
+/// struct Measurement {
+///     temp: f64,
+/// }
+/// ```
+#[proc_macro_derive(RecordFields, attributes(key, fields))]
+pub fn derive_record_fields(input: TokenStream) -> TokenStream {
+    Input::parse(input)
+        .and_then(|input| record_fields::expand(&input))
+        .unwrap_or_else(parse::Error::into_compile_error)
+}
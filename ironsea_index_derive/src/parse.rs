@@ -0,0 +1,425 @@
+//! Minimal parser for the items given to the derive macros.
+//!
+//! Only the parts of the item needed by the macros are extracted: its
+//! name, for each field its attributes, how to access it and its type,
+//! and for each variant of an `enum` its discriminant.
+
+use proc_macro::Delimiter;
+use proc_macro::Group;
+use proc_macro::Ident;
+use proc_macro::Literal;
+use proc_macro::Span;
+use proc_macro::TokenStream;
+use proc_macro::TokenTree;
+
+/// Error reported to the user as a `compile_error!` invocation.
+pub(crate) struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    pub(crate) fn new(span: Span, message: &str) -> Self {
+        Error {
+            span,
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn into_compile_error(self) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+
+        let mut group = Group::new(Delimiter::Brace, TokenTree::from(message).into());
+        group.set_span(self.span);
+
+        let tokens: Vec<TokenTree> = vec![
+            Ident::new("compile_error", self.span).into(),
+            punct('!', self.span),
+            group.into(),
+        ];
+
+        tokens.into_iter().collect()
+    }
+}
+
+fn punct(c: char, span: Span) -> TokenTree {
+    let mut p = proc_macro::Punct::new(c, proc_macro::Spacing::Alone);
+    p.set_span(span);
+    p.into()
+}
+
+/// Outer attribute of an item or a field, for example `#[key]`.
+pub(crate) struct Attribute {
+    pub(crate) name: String,
+    /// Tokens following the name of the attribute, if any.
+    pub(crate) args: TokenStream,
+    pub(crate) span: Span,
+}
+
+/// Field of a `struct`.
+pub(crate) struct Field {
+    pub(crate) attrs: Vec<Attribute>,
+    pub(crate) vis: TokenStream,
+    /// Expression accessing the field, for example `id` or `0`.
+    pub(crate) member: String,
+    pub(crate) ty: TokenStream,
+    pub(crate) span: Span,
+}
+
+impl Field {
+    pub(crate) fn has_attr(&self, name: &str) -> Option<&Attribute> {
+        self.attrs.iter().find(|a| a.name == name)
+    }
+
+    /// Type of the field, normalised for comparisons.
+    pub(crate) fn type_name(&self) -> String {
+        self.ty.to_string()
+    }
+}
+
+/// Shape of the fields of an `enum` variant.
+pub(crate) enum Shape {
+    Unit,
+    Tuple,
+    Named,
+}
+
+/// Variant of an `enum`.
+pub(crate) struct Variant {
+    pub(crate) name: Ident,
+    pub(crate) shape: Shape,
+    /// Expression given after `=`, if any.
+    pub(crate) discriminant: Option<TokenStream>,
+}
+
+impl Variant {
+    /// Pattern matching the variant, whatever its fields.
+    pub(crate) fn pattern(&self, item: &Ident) -> String {
+        let rest = match self.shape {
+            Shape::Unit => "",
+            Shape::Tuple => "(..)",
+            Shape::Named => "{ .. }",
+        };
+
+        format!("{}::{} {}", item, self.name, rest)
+    }
+}
+
+/// Item the derive macro is applied to.
+pub(crate) struct Input {
+    pub(crate) attrs: Vec<Attribute>,
+    pub(crate) vis: TokenStream,
+    pub(crate) name: Ident,
+    /// Whether the fields are named, or positional as in tuple structs.
+    pub(crate) named: bool,
+    pub(crate) fields: Vec<Field>,
+    /// Variants of an `enum`, `None` for a `struct`.
+    pub(crate) variants: Option<Vec<Variant>>,
+}
+
+impl Input {
+    pub(crate) fn has_attr(&self, name: &str) -> Option<&Attribute> {
+        self.attrs.iter().find(|a| a.name == name)
+    }
+}
+
+impl Input {
+    pub(crate) fn parse(input: TokenStream) -> Result<Self, Error> {
+        let tokens = input.into_iter().collect::<Vec<_>>();
+        let mut cursor = Cursor {
+            tokens: &tokens,
+            position: 0,
+        };
+
+        let attrs = cursor.attributes();
+        let vis = cursor.visibility();
+
+        let keyword = cursor
+            .ident()
+            .ok_or_else(|| cursor.error("expected `struct` or `enum`"))?;
+        let is_enum = match keyword.to_string().as_str() {
+            "struct" => false,
+            "enum" => true,
+            _ => {
+                return Err(Error::new(
+                    keyword.span(),
+                    "only `struct` and `enum` items are supported",
+                ))
+            }
+        };
+
+        let name = cursor
+            .ident()
+            .ok_or_else(|| cursor.error("expected the name of the item"))?;
+
+        if cursor.is_punct('<') {
+            return Err(cursor.error("generic items are not supported"));
+        }
+
+        if is_enum {
+            let variants = match cursor.next() {
+                Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+                    variants(g.stream())?
+                }
+                _ => return Err(cursor.error("expected the variants of the enum")),
+            };
+
+            return Ok(Input {
+                attrs,
+                vis,
+                name,
+                named: true,
+                fields: Vec::new(),
+                variants: Some(variants),
+            });
+        }
+
+        let (named, fields) = match cursor.next() {
+            Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+                (true, fields(g.stream(), true)?)
+            }
+            Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => {
+                (false, fields(g.stream(), false)?)
+            }
+            _ => (true, Vec::new()),
+        };
+
+        Ok(Input {
+            attrs,
+            vis,
+            name,
+            named,
+            fields,
+            variants: None,
+        })
+    }
+}
+
+/// Parse the variants of an `enum`, only keeping the shape of their
+/// fields.
+fn variants(stream: TokenStream) -> Result<Vec<Variant>, Error> {
+    let mut variants = Vec::new();
+
+    for tokens in split_on_commas(stream) {
+        let mut cursor = Cursor {
+            tokens: &tokens,
+            position: 0,
+        };
+
+        cursor.attributes();
+
+        let name = cursor
+            .ident()
+            .ok_or_else(|| cursor.error("expected the name of the variant"))?;
+
+        let shape = match cursor.peek() {
+            Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+                cursor.next();
+                Shape::Named
+            }
+            Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => {
+                cursor.next();
+                Shape::Tuple
+            }
+            _ => Shape::Unit,
+        };
+
+        let discriminant = if cursor.is_punct('=') {
+            cursor.next();
+            let expr = cursor.rest();
+            if expr.is_empty() {
+                return Err(cursor.error("expected the discriminant of the variant"));
+            }
+            Some(expr)
+        } else {
+            None
+        };
+
+        if cursor.peek().is_some() {
+            return Err(cursor.error("expected `,`"));
+        }
+
+        variants.push(Variant {
+            name,
+            shape,
+            discriminant,
+        });
+    }
+
+    Ok(variants)
+}
+
+/// Parse the fields of a `struct`, `named` telling whether they are
+/// given as `name: Type`, or only as `Type` for tuple structs.
+fn fields(stream: TokenStream, named: bool) -> Result<Vec<Field>, Error> {
+    let mut fields = Vec::new();
+
+    for (index, tokens) in split_on_commas(stream).into_iter().enumerate() {
+        let mut cursor = Cursor {
+            tokens: &tokens,
+            position: 0,
+        };
+
+        let attrs = cursor.attributes();
+        let vis = cursor.visibility();
+
+        let span = cursor.span();
+        let member = if named {
+            let ident = cursor
+                .ident()
+                .ok_or_else(|| cursor.error("expected the name of the field"))?;
+            if !cursor.is_punct(':') {
+                return Err(cursor.error("expected `:`"));
+            }
+            cursor.next();
+            ident.to_string()
+        } else {
+            index.to_string()
+        };
+
+        let ty = cursor.rest();
+        if ty.is_empty() {
+            return Err(cursor.error("expected the type of the field"));
+        }
+
+        fields.push(Field {
+            attrs,
+            vis,
+            member,
+            ty,
+            span,
+        });
+    }
+
+    Ok(fields)
+}
+
+/// Split a stream on the commas which are not enclosed in angle
+/// brackets, dropping empty trailing parts.
+pub(crate) fn split_on_commas(stream: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0usize;
+    let mut arrow = false;
+
+    for token in stream {
+        if let TokenTree::Punct(p) = &token {
+            match p.as_char() {
+                ',' if depth == 0 => {
+                    parts.push(Vec::new());
+                    continue;
+                }
+                '<' => depth += 1,
+                // Do not mistake the arrow of function types for a
+                // closing angle bracket.
+                '>' if !arrow => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+            arrow = p.as_char() == '-' && p.spacing() == proc_macro::Spacing::Joint;
+        } else {
+            arrow = false;
+        }
+
+        parts.last_mut().unwrap().push(token);
+    }
+
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+struct Cursor<'t> {
+    tokens: &'t [TokenTree],
+    position: usize,
+}
+
+impl<'t> Cursor<'t> {
+    fn peek(&self) -> Option<&'t TokenTree> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&'t TokenTree> {
+        let token = self.peek();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn span(&self) -> Span {
+        self.peek()
+            .or_else(|| self.tokens.last())
+            .map_or_else(Span::call_site, TokenTree::span)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::new(self.span(), message)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        matches!(self.peek(), Some(TokenTree::Punct(p)) if p.as_char() == c)
+    }
+
+    fn ident(&mut self) -> Option<Ident> {
+        match self.peek() {
+            Some(TokenTree::Ident(i)) => {
+                self.position += 1;
+                Some(i.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn rest(&mut self) -> TokenStream {
+        let rest = self.tokens[self.position..].iter().cloned().collect();
+        self.position = self.tokens.len();
+        rest
+    }
+
+    fn attributes(&mut self) -> Vec<Attribute> {
+        let mut attrs = Vec::new();
+
+        while self.is_punct('#') {
+            let span = self.span();
+            self.next();
+
+            if let Some(TokenTree::Group(g)) = self.next() {
+                let mut inner = g.stream().into_iter();
+                let name = match inner.next() {
+                    Some(TokenTree::Ident(i)) => i.to_string(),
+                    _ => String::new(),
+                };
+
+                attrs.push(Attribute {
+                    name,
+                    args: inner.collect(),
+                    span,
+                });
+            }
+        }
+
+        attrs
+    }
+
+    fn visibility(&mut self) -> TokenStream {
+        let start = self.position;
+
+        if !matches!(self.peek(), Some(TokenTree::Ident(i)) if i.to_string() == "pub") {
+            return TokenStream::new();
+        }
+        self.next();
+
+        // `pub(crate)`, `pub(super)`, `pub(in path)`...
+        if let Some(TokenTree::Group(g)) = self.peek() {
+            let restricted = matches!(
+                g.stream().into_iter().next(),
+                Some(TokenTree::Ident(i))
+                    if ["crate", "self", "super", "in"].contains(&i.to_string().as_str())
+            );
+            if g.delimiter() == Delimiter::Parenthesis && restricted {
+                self.next();
+            }
+        }
+
+        self.tokens[start..self.position].iter().cloned().collect()
+    }
+}
@@ -0,0 +1,112 @@
+//! Expansion of `#[derive(Record)]`.
+
+use proc_macro::TokenStream;
+
+use crate::parse::Error;
+use crate::parse::Field;
+use crate::parse::Input;
+use crate::parse::Variant;
+
+/// Integer types which can be given to `#[repr]`.
+const REPR_TYPES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+pub(crate) fn expand(input: &Input) -> Result<TokenStream, Error> {
+    if let Some(variants) = &input.variants {
+        return Ok(discriminant(input, variants).parse().unwrap());
+    }
+
+    let keys = input
+        .fields
+        .iter()
+        .filter(|f| f.has_attr("key").is_some())
+        .collect::<Vec<_>>();
+
+    if keys.is_empty() {
+        return Err(Error::new(
+            input.name.span(),
+            "expected at least one field marked with `#[key]`",
+        ));
+    }
+
+    for (i, field) in keys.iter().enumerate() {
+        let attr = field.has_attr("key").unwrap();
+        if !attr.args.is_empty() {
+            return Err(Error::new(attr.span, "`#[key]` does not take arguments"));
+        }
+
+        if keys[..i].iter().any(|f| f.type_name() == field.type_name()) {
+            return Err(Error::new(
+                field.span,
+                "multiple `#[key]` fields share the same type, which would \
+                 implement `Record` twice for the same key type",
+            ));
+        }
+    }
+
+    let mut code = String::new();
+    for field in keys {
+        code.push_str(&implementation(&input.name.to_string(), field));
+    }
+
+    Ok(code.parse().unwrap())
+}
+
+fn implementation(name: &str, field: &Field) -> String {
+    format!(
+        "impl ::ironsea_index::Record<{ty}> for {name} {{
+            fn key(&self) -> {ty} {{
+                ::core::clone::Clone::clone(&self.{member})
+            }}
+        }}",
+        ty = field.ty,
+        name = name,
+        member = field.member,
+    )
+}
+
+/// Key an `enum` by its discriminant, typed after its `#[repr]` or
+/// `isize` by default.
+///
+/// Casts are not allowed on variants with fields, so the discriminants
+/// are computed as the compiler does: starting from zero, each variant
+/// without an explicit value follows the previous one.
+fn discriminant(input: &Input, variants: &[Variant]) -> String {
+    let ty = input
+        .attrs
+        .iter()
+        .filter(|a| a.name == "repr")
+        .flat_map(|a| {
+            a.args
+                .to_string()
+                .split(|c: char| !c.is_alphanumeric())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .find(|t| REPR_TYPES.contains(&t.as_str()))
+        .unwrap_or_else(|| "isize".to_string());
+
+    let mut arms = String::new();
+    let mut value = String::from("0");
+    for (i, variant) in variants.iter().enumerate() {
+        value = match (&variant.discriminant, i) {
+            (Some(expr), _) => format!("({})", expr),
+            (None, 0) => value,
+            (None, _) => format!("({}) + 1", value),
+        };
+
+        arms.push_str(&format!("{} => {},", variant.pattern(&input.name), value));
+    }
+
+    format!(
+        "impl ::ironsea_index::Record<{ty}> for {name} {{
+            fn key(&self) -> {ty} {{
+                match *self {{ {arms} }}
+            }}
+        }}",
+        ty = ty,
+        name = input.name,
+        arms = arms,
+    )
+}
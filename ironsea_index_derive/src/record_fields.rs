@@ -0,0 +1,171 @@
+//! Expansion of `#[derive(RecordFields)]`.
+
+use proc_macro::TokenStream;
+use proc_macro::TokenTree;
+
+use crate::parse::split_on_commas;
+use crate::parse::Attribute;
+use crate::parse::Error;
+use crate::parse::Field;
+use crate::parse::Input;
+
+/// Options given through `#[fields(...)]`.
+#[derive(Default)]
+struct Options {
+    /// Name of the struct to generate, instead of returning a tuple.
+    name: Option<String>,
+    /// Content of `derive(...)`, applied to the generated struct.
+    derive: Option<TokenStream>,
+}
+
+impl Options {
+    fn parse(attr: Option<&Attribute>) -> Result<Self, Error> {
+        let mut options = Options::default();
+
+        let attr = match attr {
+            None => return Ok(options),
+            Some(attr) => attr,
+        };
+
+        let invalid = || {
+            Error::new(
+                attr.span,
+                "expected `#[fields(struct = \"Name\")]`, optionally followed by `derive(...)`",
+            )
+        };
+
+        let mut args = attr.args.clone().into_iter();
+        let group = match (args.next(), args.next()) {
+            (Some(TokenTree::Group(group)), None) => group,
+            _ => return Err(invalid()),
+        };
+
+        for option in split_on_commas(group.stream()) {
+            match option.as_slice() {
+                [TokenTree::Ident(k), TokenTree::Punct(eq), TokenTree::Literal(name)]
+                    if k.to_string() == "struct" && eq.as_char() == '=' =>
+                {
+                    let name = name.to_string();
+                    let name = name.trim_matches('"');
+                    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                        return Err(invalid());
+                    }
+                    options.name = Some(name.to_string());
+                }
+                [TokenTree::Ident(k), TokenTree::Group(traits)] if k.to_string() == "derive" => {
+                    options.derive = Some(traits.stream());
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        if options.name.is_none() {
+            return Err(invalid());
+        }
+
+        Ok(options)
+    }
+}
+
+pub(crate) fn expand(input: &Input) -> Result<TokenStream, Error> {
+    if input.variants.is_some() {
+        return Err(Error::new(
+            input.name.span(),
+            "`RecordFields` cannot be derived for an `enum`",
+        ));
+    }
+
+    let options = Options::parse(input.has_attr("fields"))?;
+
+    let fields = input
+        .fields
+        .iter()
+        .filter(|f| f.has_attr("key").is_none())
+        .collect::<Vec<_>>();
+
+    let code = match &options.name {
+        None => tuple(input, &fields),
+        Some(name) => structure(input, &fields, name, &options),
+    };
+
+    Ok(code.parse().unwrap())
+}
+
+fn clone(field: &Field) -> String {
+    format!("::core::clone::Clone::clone(&self.{})", field.member)
+}
+
+/// Return the fields as a tuple, in declaration order.
+fn tuple(input: &Input, fields: &[&Field]) -> String {
+    let types = fields
+        .iter()
+        .map(|f| format!("{},", f.ty))
+        .collect::<String>();
+    let values = fields
+        .iter()
+        .map(|f| format!("{},", clone(f)))
+        .collect::<String>();
+
+    format!(
+        "impl ::ironsea_index::RecordFields<({types})> for {name} {{
+            fn fields(&self) -> ({types}) {{
+                ({values})
+            }}
+        }}",
+        types = types,
+        values = values,
+        name = input.name,
+    )
+}
+
+/// Return the fields as a generated struct, which follows the style,
+/// named or tuple, of the input.
+fn structure(input: &Input, fields: &[&Field], name: &str, options: &Options) -> String {
+    let derive = options
+        .derive
+        .as_ref()
+        .map(|traits| format!("#[derive({})]", traits))
+        .unwrap_or_default();
+
+    let (declaration, value) = if input.named {
+        let declaration = fields
+            .iter()
+            .map(|f| format!("{} {}: {},", f.vis, f.member, f.ty))
+            .collect::<String>();
+        let value = fields
+            .iter()
+            .map(|f| format!("{}: {},", f.member, clone(f)))
+            .collect::<String>();
+
+        (format!("{{ {} }}", declaration), format!("{{ {} }}", value))
+    } else {
+        let declaration = fields
+            .iter()
+            .map(|f| format!("{} {},", f.vis, f.ty))
+            .collect::<String>();
+        let value = fields
+            .iter()
+            .map(|f| format!("{},", clone(f)))
+            .collect::<String>();
+
+        (format!("({});", declaration), format!("({})", value))
+    };
+
+    format!(
+        "#[doc = \"Fields of `{input}`, excluding its keys.\"]
+        {derive}
+        {vis} struct {name} {declaration}
+
+        impl ::ironsea_index::RecordFields<{name}> for {input} {{
+            fn fields(&self) -> {name} {{
+                {name} {value}
+            }}
+        }}",
+        input = input.name,
+        derive = derive,
+        vis = input.vis,
+        name = name,
+        declaration = declaration,
+        value = value,
+    )
+}
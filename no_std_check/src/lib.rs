@@ -0,0 +1,76 @@
+//! Checks that the traits of `ironsea_index` can be used without the
+//! standard library.
+//!
+//! Build it on its own, so that the features enabled by the other
+//! members of the workspace do not leak into `ironsea_index`:
+//!
+//! ```sh
+//! cargo build -p no_std_check
+//! ```
+
+#![no_std]
+#![forbid(unsafe_code)]
+#![deny(missing_docs)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+use ironsea_index::impls::SortedVecIndex;
+use ironsea_index::Indexed;
+use ironsea_index::IndexedDestructured;
+use ironsea_index::Record;
+use ironsea_index::RecordFields;
+
+/// Record used to exercise the traits.
+#[derive(Clone, Debug)]
+pub struct Point {
+    /// Key of the record.
+    pub id: u32,
+    /// Value of the record.
+    pub value: i64,
+}
+
+impl Record<u32> for Point {
+    fn key(&self) -> u32 {
+        self.id
+    }
+}
+
+impl RecordFields<i64> for Point {
+    fn fields(&self) -> i64 {
+        self.value
+    }
+}
+
+/// Destructured index over points.
+pub struct Points(pub Vec<(u32, i64)>);
+
+impl IndexedDestructured<i64, u32> for Points {
+    fn find<'i>(&'i self, key: &u32) -> Box<dyn Iterator<Item = &'i i64> + 'i> {
+        let key = *key;
+        Box::new(self.0.iter().filter(move |p| p.0 == key).map(|p| &p.1))
+    }
+
+    fn find_range<'i>(
+        &'i self,
+        start: &u32,
+        end: &u32,
+    ) -> Box<dyn Iterator<Item = (u32, &'i i64)> + 'i> {
+        let range = *start..=*end;
+        Box::new(
+            self.0
+                .iter()
+                .filter(move |p| range.contains(&p.0))
+                .map(|p| (p.0, &p.1)),
+        )
+    }
+}
+
+/// Sum the values of the points whose key is in the given range.
+pub fn sum<B: RangeBounds<u32>>(points: Vec<Point>, range: B) -> i64 {
+    let index = SortedVecIndex::new(points);
+    index.find_range_bounds(range).map(|p| p.fields()).sum()
+}
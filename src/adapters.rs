@@ -0,0 +1,25 @@
+//! Adapters providing a different view of an existing index, without
+//! rebuilding it.
+
+mod bloom;
+#[cfg(feature = "std")]
+mod caching;
+#[cfg(feature = "std")]
+mod concurrent;
+mod filtered;
+mod mapped_key;
+mod mapped_record;
+mod reconstructed;
+mod union;
+
+pub use bloom::BloomIndex;
+#[cfg(feature = "std")]
+pub use caching::CachingIndex;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentIndex;
+pub use filtered::FilteredIndex;
+pub use mapped_key::MappedKeyIndex;
+pub use mapped_key::MonotonicKeyIndex;
+pub use mapped_record::MappedRecordIndex;
+pub use reconstructed::Reconstructed;
+pub use union::UnionIndex;
@@ -0,0 +1,230 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::hash::Hasher;
+use core::iter;
+use core::marker::PhantomData;
+use core::ops::Bound;
+
+use crate::Indexed;
+use crate::KeyEnumerable;
+use crate::MembershipFilter;
+
+/// Index front-end rejecting the lookups of absent keys with a bloom
+/// filter, without touching the wrapped index.
+///
+/// The filter is populated with the keys of the wrapped index when the
+/// adapter is built, so the wrapped index must not be modified
+/// afterwards. As a bloom filter can return false positives, but no
+/// false negatives, a key rejected by the filter is definitely absent,
+/// while a key accepted by the filter is looked up in the wrapped index.
+///
+/// Only point lookups are filtered, range queries are forwarded to the
+/// wrapped index.
+///
+///  * `I`: Type of the wrapped index
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::collections::BTreeMap;
+/// use std::ops::Bound;
+///
+/// use ironsea_index::adapters::BloomIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::KeyEnumerable;
+/// use ironsea_index::MembershipFilter;
+///
+/// // Index counting its lookups.
+/// struct Index(BTreeMap<u32, Vec<u32>>, Cell<usize>);
+///
+/// impl Indexed<u32, u32> for Index {
+///     fn find<'i>(&'i self, key: &u32) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+///         self.1.set(self.1.get() + 1);
+///         self.0.find(key)
+///     }
+///
+///     fn find_bounds<'i>(
+///         &'i self,
+///         start: Bound<&u32>,
+///         end: Bound<&u32>,
+///     ) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+///         self.0.find_bounds(start, end)
+///     }
+/// }
+///
+/// impl KeyEnumerable<u32> for Index {
+///     fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+///         Box::new(self.0.keys())
+///     }
+/// }
+///
+/// let mut map = BTreeMap::new();
+/// map.insert(10, vec![1]);
+/// map.insert(20, vec![2, 3]);
+/// let index = BloomIndex::new(Index(map, Cell::new(0)), 10);
+///
+/// // Present keys are never rejected.
+/// assert!(index.might_contain(&10));
+/// assert_eq!(index.find(&20).collect::<Vec<_>>(), vec![&2, &3]);
+/// assert_eq!(index.get_ref().1.get(), 1);
+///
+/// // An absent key rejected by the filter does not reach the index.
+/// assert!(!index.might_contain(&15));
+/// assert_eq!(index.find(&15).count(), 0);
+/// assert_eq!(index.get_ref().1.get(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BloomIndex<I, K> {
+    inner: I,
+    bits: Vec<u64>,
+    hashes: u32,
+    key: PhantomData<fn(&K)>,
+}
+
+impl<I, K> BloomIndex<I, K>
+where
+    I: KeyEnumerable<K>,
+    K: Hash,
+{
+    /// Wrap `inner`, building a filter of `bits_per_key` bits for every
+    /// key of `inner`.
+    ///
+    /// The more bits per key, the fewer false positives: 10 bits per
+    /// key give a false positive rate of about 1%.
+    pub fn new(inner: I, bits_per_key: usize) -> Self {
+        let bits_per_key = bits_per_key.max(1);
+        let len = (inner.keys().count() * bits_per_key).max(64);
+
+        // The optimal number of hash functions is ln(2) times the number
+        // of bits per key.
+        let hashes = (bits_per_key * 69 / 100).clamp(1, 30) as u32;
+
+        let mut filter = BloomIndex {
+            bits: vec![0; len.div_ceil(64)],
+            hashes,
+            inner,
+            key: PhantomData,
+        };
+
+        let positions = filter
+            .inner
+            .keys()
+            .flat_map(|key| filter.positions(key))
+            .collect::<Vec<_>>();
+        for position in positions {
+            filter.bits[position / 64] |= 1 << (position % 64);
+        }
+
+        filter
+    }
+}
+
+impl<I, K> BloomIndex<I, K> {
+    /// Retrieve a reference to the wrapped index.
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    /// Retrieve the wrapped index.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Bits of the filter corresponding to `key`, computed by double
+    /// hashing.
+    fn positions(&self, key: &K) -> impl Iterator<Item = usize>
+    where
+        K: Hash,
+    {
+        let mut hasher = Fnv1a::default();
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let len = (self.bits.len() * 64) as u64;
+        let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+
+        (0..u64::from(self.hashes))
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+}
+
+impl<I, K> MembershipFilter<K> for BloomIndex<I, K>
+where
+    K: Hash,
+{
+    fn might_contain(&self, key: &K) -> bool {
+        self.positions(key)
+            .all(|position| self.bits[position / 64] & (1 << (position % 64)) != 0)
+    }
+}
+
+impl<R, K, I> Indexed<R, K> for BloomIndex<I, K>
+where
+    I: Indexed<R, K>,
+    K: Hash,
+{
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        if !self.might_contain(key) {
+            return Box::new(iter::empty());
+        }
+
+        self.inner.find(key)
+    }
+
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.inner.find_bounds(start, end)
+    }
+
+    fn count(&self, key: &K) -> usize {
+        if !self.might_contain(key) {
+            return 0;
+        }
+
+        self.inner.count(key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.might_contain(key) && self.inner.contains_key(key)
+    }
+
+    fn find_range_rev<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.inner.find_range_rev(start, end)
+    }
+}
+
+/// FNV-1a hash, followed by a finalizer mixing the bits, as the filter
+/// splits the hash in two halves.
+///
+/// The standard library hashers are not available without `std`.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        // Finalizer of `splitmix64`.
+        let mut h = self.0;
+        h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        h ^ (h >> 31)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::hash::Hash;
+use std::collections::HashMap;
+
+use crate::Indexed;
+
+/// View of an index caching the results of its most recent point
+/// lookups.
+///
+/// References to the cached records cannot outlive an update of the
+/// cache, so unlike [`Indexed::find`], [`find`](CachingIndex::find)
+/// returns clones of the records, as
+/// [`IndexedOwned::find_owned`](crate::IndexedOwned::find_owned) does.
+/// For the same reason, this does not implement [`Indexed`].
+///
+/// When the cache is full, the least recently used key is evicted. The
+/// cache is kept in a `RefCell`, so the adapter cannot be shared between
+/// threads.
+///
+/// This requires the `std` feature.
+///
+///  * `I`: Type of the wrapped index
+///  * `K`: Type of the keys
+///  * `R`: Type of the records
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::collections::BTreeMap;
+/// use std::ops::Bound;
+///
+/// use ironsea_index::adapters::CachingIndex;
+/// use ironsea_index::Indexed;
+///
+/// // Index counting its lookups.
+/// struct Index(BTreeMap<u32, Vec<u32>>, Cell<usize>);
+///
+/// impl Indexed<u32, u32> for Index {
+///     fn find<'i>(&'i self, key: &u32) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+///         self.1.set(self.1.get() + 1);
+///         self.0.find(key)
+///     }
+///
+///     fn find_bounds<'i>(
+///         &'i self,
+///         start: Bound<&u32>,
+///         end: Bound<&u32>,
+///     ) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+///         self.0.find_bounds(start, end)
+///     }
+/// }
+///
+/// let map = (0..10).map(|k| (k, vec![k * 10])).collect();
+/// let index = CachingIndex::with_capacity(Index(map, Cell::new(0)), 2);
+///
+/// assert_eq!(index.find(&1), vec![10]);
+/// assert_eq!(index.find(&1), vec![10]);
+/// assert_eq!(index.get_ref().1.get(), 1);
+///
+/// // Looking up two other keys evicts the least recently used one.
+/// index.find(&2);
+/// index.find(&3);
+/// assert_eq!(index.get_ref().1.get(), 3);
+/// index.find(&1);
+/// assert_eq!(index.get_ref().1.get(), 4);
+/// ```
+#[derive(Debug)]
+pub struct CachingIndex<I, K, R> {
+    inner: I,
+    capacity: usize,
+    cache: RefCell<Lru<K, R>>,
+}
+
+/// Least recently used cache of the results of point lookups.
+#[derive(Debug)]
+struct Lru<K, R> {
+    /// Results by key, with the tick of their last use.
+    entries: HashMap<K, (u64, Vec<R>)>,
+    /// Keys by the tick of their last use.
+    uses: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<I, K, R> CachingIndex<I, K, R>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Wrap `inner`, caching the results of up to `capacity` keys.
+    ///
+    /// With a capacity of zero, nothing is cached.
+    pub fn with_capacity(inner: I, capacity: usize) -> Self {
+        CachingIndex {
+            inner,
+            capacity,
+            cache: RefCell::new(Lru {
+                entries: HashMap::with_capacity(capacity),
+                uses: BTreeMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    /// Retrieve clones of all records matching the key, from the cache
+    /// if the key was looked up recently.
+    pub fn find(&self, key: &K) -> Vec<R>
+    where
+        I: Indexed<R, K>,
+        R: Clone,
+    {
+        let mut cache = self.cache.borrow_mut();
+        let cache = &mut *cache;
+
+        cache.tick += 1;
+        let tick = cache.tick;
+
+        if let Some((used, records)) = cache.entries.get_mut(key) {
+            cache.uses.remove(used);
+            cache.uses.insert(tick, key.clone());
+            *used = tick;
+
+            return records.clone();
+        }
+
+        let records = self.inner.find(key).cloned().collect::<Vec<_>>();
+        if self.capacity == 0 {
+            return records;
+        }
+
+        if cache.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = cache.uses.pop_first() {
+                cache.entries.remove(&oldest);
+            }
+        }
+        cache.uses.insert(tick, key.clone());
+        cache.entries.insert(key.clone(), (tick, records.clone()));
+
+        records
+    }
+}
+
+impl<I, K, R> CachingIndex<I, K, R> {
+    /// Retrieve a reference to the wrapped index.
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    /// Retrieve the wrapped index.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
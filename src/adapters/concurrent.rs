@@ -0,0 +1,122 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+use crate::Indexed;
+
+/// Index shared between threads, which can be replaced as a whole while
+/// it is being read.
+///
+/// The index is held in an `Arc`, which [`swap`](ConcurrentIndex::swap)
+/// replaces atomically. Each query runs on the version of the index
+/// current when it starts, so it always sees a consistent snapshot, even
+/// if the index is replaced meanwhile. Readers only hold a lock while
+/// cloning the `Arc`, never while running their queries, so rebuilding
+/// the next version of an index does not block them.
+///
+/// References into a version cannot outlive it, so unlike
+/// [`Indexed::find`], [`find`](ConcurrentIndex::find) and
+/// [`find_range`](ConcurrentIndex::find_range) return clones of the
+/// records. To run several queries on the same version, or to avoid the
+/// clones, query the index returned by
+/// [`snapshot`](ConcurrentIndex::snapshot) instead.
+///
+/// This requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use ironsea_index::adapters::ConcurrentIndex;
+/// use ironsea_index::Indexed;
+///
+/// // Every record of a version holds the number of that version.
+/// let version = |v: u32| (0..100).map(|k| (k, vec![v])).collect::<BTreeMap<_, _>>();
+///
+/// let index = Arc::new(ConcurrentIndex::new(version(0)));
+///
+/// let snapshot = index.snapshot();
+/// assert_eq!(index.find(&1), vec![0]);
+/// index.swap(version(1));
+/// assert_eq!(index.find(&1), vec![1]);
+///
+/// // The snapshot is not affected by the swap.
+/// assert_eq!(snapshot.find(&1).collect::<Vec<_>>(), vec![&0]);
+///
+/// // Queries never see records of two versions.
+/// let writer = {
+///     let index = Arc::clone(&index);
+///     thread::spawn(move || {
+///         for v in 2..50 {
+///             index.swap(version(v));
+///         }
+///     })
+/// };
+/// for _ in 0..50 {
+///     let records = index.find_range(&0, &99);
+///     assert_eq!(records.len(), 100);
+///     assert!(records.iter().all(|v| *v == records[0]));
+/// }
+/// writer.join().unwrap();
+///
+/// assert_eq!(index.find(&1), vec![49]);
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentIndex<I> {
+    current: RwLock<Arc<I>>,
+}
+
+impl<I> ConcurrentIndex<I> {
+    /// Share `inner` between threads.
+    pub fn new(inner: I) -> Self {
+        ConcurrentIndex {
+            current: RwLock::new(Arc::new(inner)),
+        }
+    }
+
+    /// Retrieve the current version of the index.
+    ///
+    /// The version returned stays valid, and unchanged, after the index
+    /// is replaced.
+    pub fn snapshot(&self) -> Arc<I> {
+        // The lock only protects the replacement of an `Arc`, which
+        // cannot be left half done.
+        let current = self.current.read().unwrap_or_else(PoisonError::into_inner);
+
+        Arc::clone(&current)
+    }
+
+    /// Replace the index by `inner`, returning the previous version.
+    ///
+    /// Queries already running complete on the previous version.
+    pub fn swap(&self, inner: I) -> Arc<I> {
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+
+        core::mem::replace(&mut *current, Arc::new(inner))
+    }
+
+    /// Retrieve clones of all records matching the key, in the current
+    /// version of the index.
+    pub fn find<R, K>(&self, key: &K) -> Vec<R>
+    where
+        I: Indexed<R, K>,
+        R: Clone,
+    {
+        self.snapshot().find(key).cloned().collect()
+    }
+
+    /// Retrieve clones of all records matching in the key range defined
+    /// by `start` and `end`, both included, in the current version of
+    /// the index.
+    pub fn find_range<R, K>(&self, start: &K, end: &K) -> Vec<R>
+    where
+        I: Indexed<R, K>,
+        R: Clone,
+    {
+        self.snapshot().find_range(start, end).cloned().collect()
+    }
+}
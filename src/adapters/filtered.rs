@@ -0,0 +1,97 @@
+use alloc::boxed::Box;
+use core::ops::Bound;
+
+use crate::Indexed;
+
+/// View of an index restricted to the records matching a predicate.
+///
+/// The records are filtered as they are returned by the wrapped index,
+/// so the results keep the order of the wrapped index, and in particular
+/// range queries are still in key order.
+///
+///  * `I`: Type of the wrapped index
+///  * `P`: Type of the predicate
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::adapters::FilteredIndex;
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// struct Player {
+///     team: u32,
+///     score: u32,
+/// }
+///
+/// impl Record<u32> for Player {
+///     fn key(&self) -> u32 {
+///         self.team
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![
+///     Player { team: 2, score: 80 },
+///     Player { team: 1, score: 40 },
+///     Player { team: 1, score: 95 },
+///     Player { team: 2, score: 10 },
+/// ]);
+///
+/// let best = FilteredIndex::new(index, |p: &Player| p.score >= 50);
+///
+/// assert_eq!(best.find(&1).map(|p| p.score).collect::<Vec<_>>(), vec![95]);
+/// assert_eq!(
+///     best.find_range(&1, &2).map(|p| p.team).collect::<Vec<_>>(),
+///     vec![1, 2]
+/// );
+/// assert_eq!(best.count(&2), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FilteredIndex<I, P> {
+    inner: I,
+    predicate: P,
+}
+
+impl<I, P> FilteredIndex<I, P> {
+    /// Wrap `inner`, only returning the records for which `predicate`
+    /// returns `true`.
+    pub fn new(inner: I, predicate: P) -> Self {
+        FilteredIndex { inner, predicate }
+    }
+
+    /// Retrieve the wrapped index.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<R, K, I, P> Indexed<R, K> for FilteredIndex<I, P>
+where
+    I: Indexed<R, K>,
+    P: Fn(&R) -> bool,
+{
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        Box::new(self.inner.find(key).filter(move |r| (self.predicate)(r)))
+    }
+
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        Box::new(
+            self.inner
+                .find_bounds(start, end)
+                .filter(move |r| (self.predicate)(r)),
+        )
+    }
+
+    fn find_range_rev<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        Box::new(
+            self.inner
+                .find_range_rev(start, end)
+                .filter(move |r| (self.predicate)(r)),
+        )
+    }
+}
@@ -0,0 +1,145 @@
+use alloc::boxed::Box;
+use core::ops::Bound;
+
+use crate::Indexed;
+
+/// View of an index queried with keys of a different type, translated
+/// by a function before each lookup.
+///
+/// Only point lookups are provided, as an arbitrary translation does not
+/// preserve the order of the keys. When it does, use
+/// [`MonotonicKeyIndex`] instead, which also provides range queries.
+///
+///  * `I`: Type of the wrapped index
+///  * `F`: Type of the function translating the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::adapters::MappedKeyIndex;
+///
+/// let mut index = BTreeMap::new();
+/// index.insert("alice".to_string(), vec![1]);
+/// index.insert("bob".to_string(), vec![2, 3]);
+///
+/// // Case-insensitive lookups, on an index of lowercase keys.
+/// let index = MappedKeyIndex::new(index, |name: &&str| name.to_lowercase());
+///
+/// assert_eq!(index.find(&"Bob").collect::<Vec<_>>(), vec![&2, &3]);
+/// assert_eq!(index.find(&"carol").count(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MappedKeyIndex<I, F> {
+    inner: I,
+    map: F,
+}
+
+impl<I, F> MappedKeyIndex<I, F> {
+    /// Wrap `inner`, translating the keys of the queries with `map`.
+    pub fn new(inner: I, map: F) -> Self {
+        MappedKeyIndex { inner, map }
+    }
+
+    /// Retrieve the wrapped index.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Retrieve all records matching the translated key.
+    pub fn find<'i, R, K, Q>(&'i self, key: &Q) -> Box<dyn Iterator<Item = &'i R> + 'i>
+    where
+        I: Indexed<R, K>,
+        F: Fn(&Q) -> K,
+    {
+        self.inner.find(&(self.map)(key))
+    }
+
+    /// Assert the translation preserves the order of the keys, to enable
+    /// range queries.
+    ///
+    /// See [`MonotonicKeyIndex`] for the exact requirements.
+    pub fn assume_monotonic(self) -> MonotonicKeyIndex<I, F> {
+        MonotonicKeyIndex::new(self.inner, self.map)
+    }
+}
+
+/// View of an index queried with keys of a different type, translated
+/// by an order-preserving function before each lookup.
+///
+/// The translation must be strictly increasing: for any keys `a < b`,
+/// `map(a) < map(b)` must hold. This is what allows translating the
+/// bounds of a range, excluded bounds included. If this does not hold,
+/// range queries return unspecified results.
+///
+///  * `I`: Type of the wrapped index
+///  * `F`: Type of the function translating the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::adapters::MonotonicKeyIndex;
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct UserId(u32);
+///
+/// struct User(u32, &'static str);
+///
+/// impl Record<u32> for User {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![User(3, "carol"), User(1, "alice"), User(2, "bob")]);
+/// let index = MonotonicKeyIndex::new(index, |id: &UserId| id.0);
+///
+/// let names = |users: Box<dyn Iterator<Item = &User> + '_>| users.map(|u| u.1).collect::<Vec<_>>();
+///
+/// assert_eq!(names(index.find(&UserId(2))), vec!["bob"]);
+/// assert_eq!(names(index.find_range(&UserId(2), &UserId(3))), vec!["bob", "carol"]);
+/// assert_eq!(names(index.find_range_bounds(..UserId(3))), vec!["alice", "bob"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MonotonicKeyIndex<I, F> {
+    inner: I,
+    map: F,
+}
+
+impl<I, F> MonotonicKeyIndex<I, F> {
+    /// Wrap `inner`, translating the keys of the queries with `map`,
+    /// which must be strictly increasing.
+    pub fn new(inner: I, map: F) -> Self {
+        MonotonicKeyIndex { inner, map }
+    }
+
+    /// Retrieve the wrapped index.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<R, K, Q, I, F> Indexed<R, Q> for MonotonicKeyIndex<I, F>
+where
+    I: Indexed<R, K>,
+    F: Fn(&Q) -> K,
+{
+    fn find<'i>(&'i self, key: &Q) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.inner.find(&(self.map)(key))
+    }
+
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&Q>,
+        end: Bound<&Q>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let start = start.map(&self.map);
+        let end = end.map(&self.map);
+
+        self.inner.find_bounds(start.as_ref(), end.as_ref())
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::vec::Vec;
+
+use crate::Indexed;
+
+/// View of an index whose records are transformed by a function as they
+/// are retrieved.
+///
+/// The transformed records are computed at query time, so they cannot
+/// be borrowed from the index. Unlike [`Indexed::find`], the queries of
+/// this adapter therefore return owned records, and it does not
+/// implement [`Indexed`].
+///
+///  * `I`: Type of the wrapped index
+///  * `F`: Type of the function transforming the records
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::adapters::MappedRecordIndex;
+///
+/// let mut index = BTreeMap::new();
+/// index.insert(1, vec![1250_u32]);
+/// index.insert(2, vec![399, 5]);
+///
+/// // Format the prices, in cents, only when they are looked up.
+/// let index = MappedRecordIndex::new(index, |cents: &u32| {
+///     format!("{}.{:02}", cents / 100, cents % 100)
+/// });
+///
+/// assert_eq!(index.find(&1), vec!["12.50"]);
+/// assert_eq!(index.find_range(&1, &2), vec!["12.50", "3.99", "0.05"]);
+/// assert!(index.find(&3).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MappedRecordIndex<I, F> {
+    inner: I,
+    map: F,
+}
+
+impl<I, F> MappedRecordIndex<I, F> {
+    /// Wrap `inner`, transforming its records with `map` when they are
+    /// retrieved.
+    pub fn new(inner: I, map: F) -> Self {
+        MappedRecordIndex { inner, map }
+    }
+
+    /// Retrieve a reference to the wrapped index.
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    /// Retrieve the wrapped index.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Retrieve the transformed records matching the key.
+    pub fn find<R, K, T>(&self, key: &K) -> Vec<T>
+    where
+        I: Indexed<R, K>,
+        F: Fn(&R) -> T,
+    {
+        self.inner.find(key).map(&self.map).collect()
+    }
+
+    /// Retrieve the transformed records matching in the key range
+    /// defined by `start` and `end`, both included.
+    pub fn find_range<R, K, T>(&self, start: &K, end: &K) -> Vec<T>
+    where
+        I: Indexed<R, K>,
+        F: Fn(&R) -> T,
+    {
+        self.inner.find_range(start, end).map(&self.map).collect()
+    }
+}
@@ -0,0 +1,142 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::IndexedDestructured;
+use crate::RecordBuilder;
+
+/// View of a destructuring index as an index of whole records, rebuilt
+/// with `B` when they are retrieved.
+///
+/// The destructuring index no longer stores the original records, so
+/// they are synthesized from their key and fields for every query. They
+/// cannot be borrowed from the index, so instead of implementing
+/// [`Indexed`](crate::Indexed), this provides the methods of
+/// [`IndexedOwned`](crate::IndexedOwned), returning owned records. The
+/// trait itself is implemented for every `Indexed` type, which prevents
+/// implementing it here as well.
+///
+///  * `D`: Type of the wrapped destructuring index
+///  * `R`: Type of the records
+///  * `B`: Type of the [`RecordBuilder`] implementation
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::adapters::Reconstructed;
+/// use ironsea_index::IndexedDestructured;
+/// use ironsea_index::Record;
+/// use ironsea_index::RecordBuilder;
+/// use ironsea_index::RecordFields;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Measurement {
+///     t: i64,
+///     temp: u32,
+/// }
+///
+/// impl Record<i64> for Measurement {
+///     fn key(&self) -> i64 {
+///         self.t
+///     }
+/// }
+///
+/// impl RecordFields<u32> for Measurement {
+///     fn fields(&self) -> u32 {
+///         self.temp
+///     }
+/// }
+///
+/// impl RecordBuilder<Measurement, i64, u32> for Measurement {
+///     fn build(t: i64, temp: u32) -> Measurement {
+///         Measurement { t, temp }
+///     }
+/// }
+///
+/// // Destructuring index, storing the temperatures by timestamp.
+/// struct Index(Vec<(i64, u32)>);
+///
+/// # impl IndexedDestructured<u32, i64> for Index {
+/// #     fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+/// #         let key = *key;
+/// #         Box::new(self.0.iter().filter(move |(k, _)| *k == key).map(|(_, f)| f))
+/// #     }
+/// #
+/// #     fn find_range<'i>(
+/// #         &'i self,
+/// #         start: &i64,
+/// #         end: &i64,
+/// #     ) -> Box<dyn Iterator<Item = (i64, &'i u32)> + 'i> {
+/// #         let (start, end) = (*start, *end);
+/// #         Box::new(
+/// #             self.0
+/// #                 .iter()
+/// #                 .filter(move |(k, _)| start <= *k && *k <= end)
+/// #                 .map(|(k, f)| (*k, f)),
+/// #         )
+/// #     }
+/// # }
+/// #
+/// let records = vec![
+///     Measurement { t: 1, temp: 20 },
+///     Measurement { t: 2, temp: 22 },
+///     Measurement { t: 4, temp: 19 },
+/// ];
+/// let index = Index(records.iter().map(|r| (r.key(), r.fields())).collect());
+///
+/// let index = Reconstructed::<_, Measurement, Measurement>::new(index);
+///
+/// assert_eq!(index.find_owned(&2), vec![records[1].clone()]);
+/// assert_eq!(index.find_range_owned(&0, &9), records);
+/// assert!(index.find_owned(&3).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Reconstructed<D, R, B> {
+    inner: D,
+    builder: PhantomData<fn() -> (R, B)>,
+}
+
+impl<D, R, B> Reconstructed<D, R, B> {
+    /// Wrap `inner`, rebuilding its records with `B`.
+    pub fn new(inner: D) -> Self {
+        Reconstructed {
+            inner,
+            builder: PhantomData,
+        }
+    }
+
+    /// Retrieve a reference to the wrapped index.
+    pub fn get_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Retrieve the wrapped index.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    /// Retrieve all records matching the key, rebuilt from their key
+    /// and fields.
+    pub fn find_owned<F, K>(&self, key: &K) -> Vec<R>
+    where
+        D: IndexedDestructured<F, K>,
+        B: RecordBuilder<R, K, F>,
+        K: Clone,
+        F: Clone,
+    {
+        self.inner.find_rebuilt::<R, B>(key)
+    }
+
+    /// Retrieve all records matching in the key range defined by `start`
+    /// and `end`, both included, rebuilt from their key and fields.
+    pub fn find_range_owned<F, K>(&self, start: &K, end: &K) -> Vec<R>
+    where
+        D: IndexedDestructured<F, K>,
+        B: RecordBuilder<R, K, F>,
+        F: Clone,
+    {
+        self.inner
+            .find_range(start, end)
+            .map(|(key, fields)| B::build(key, fields.clone()))
+            .collect()
+    }
+}
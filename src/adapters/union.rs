@@ -0,0 +1,129 @@
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+use core::iter;
+use core::ops::Bound;
+
+use crate::Indexed;
+use crate::Record;
+
+/// View of two indices as a single one, for example when the records
+/// are sharded across them.
+///
+/// Point lookups return the records of the first index, followed by the
+/// ones of the second. Range queries merge the results of both indices,
+/// so they are returned in key order; when both indices hold records for
+/// the same key, the ones of the first index come first.
+///
+///  * `A`: Type of the first index
+///  * `B`: Type of the second index
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::adapters::UnionIndex;
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Event(u32, char);
+///
+/// impl Record<u32> for Event {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let even = SortedVecIndex::new(vec![Event(2, 'a'), Event(4, 'b'), Event(6, 'c')]);
+/// let odd = SortedVecIndex::new(vec![Event(1, 'd'), Event(4, 'e'), Event(5, 'f')]);
+/// let index = UnionIndex::new(even, odd);
+///
+/// let keys = index.find_range(&1, &5).map(|e| e.0).collect::<Vec<_>>();
+/// assert_eq!(keys, vec![1, 2, 4, 4, 5]);
+/// assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+///
+/// let found = index.find(&4).map(|e| e.1).collect::<Vec<_>>();
+/// assert_eq!(found, vec!['b', 'e']);
+/// ```
+#[derive(Clone, Debug)]
+pub struct UnionIndex<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> UnionIndex<A, B> {
+    /// Combine `first` and `second` into a single index.
+    pub fn new(first: A, second: B) -> Self {
+        UnionIndex { first, second }
+    }
+
+    /// Retrieve the combined indices.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+/// Merge two sequences of records sorted by key, in ascending order when
+/// `ordering` is `Less`, and descending order when it is `Greater`.
+///
+/// On equal keys, the records of `left` come first.
+fn merge<'i, R, K>(
+    left: Box<dyn Iterator<Item = &'i R> + 'i>,
+    right: Box<dyn Iterator<Item = &'i R> + 'i>,
+    ordering: Ordering,
+) -> Box<dyn Iterator<Item = &'i R> + 'i>
+where
+    R: Record<K> + 'i,
+    K: Ord,
+{
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+
+    Box::new(iter::from_fn(move || match (left.peek(), right.peek()) {
+        (Some(l), Some(r)) => match l.key().cmp(&r.key()) {
+            Ordering::Equal => left.next(),
+            o if o == ordering => left.next(),
+            _ => right.next(),
+        },
+        (Some(_), None) => left.next(),
+        (None, _) => right.next(),
+    }))
+}
+
+impl<R, K, A, B> Indexed<R, K> for UnionIndex<A, B>
+where
+    A: Indexed<R, K>,
+    B: Indexed<R, K>,
+    R: Record<K>,
+    K: Ord,
+{
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        Box::new(self.first.find(key).chain(self.second.find(key)))
+    }
+
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        merge(
+            self.first.find_bounds(start, end),
+            self.second.find_bounds(start, end),
+            Ordering::Less,
+        )
+    }
+
+    fn count(&self, key: &K) -> usize {
+        self.first.count(key) + self.second.count(key)
+    }
+
+    fn find_range_rev<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        // Equal keys are taken from the second index first, to return
+        // exactly the reverse of `find_range`.
+        merge(
+            self.second.find_range_rev(start, end),
+            self.first.find_range_rev(start, end),
+            Ordering::Greater,
+        )
+    }
+}
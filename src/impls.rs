@@ -0,0 +1,193 @@
+//! Index implementations provided by this crate.
+//!
+//! Besides the types defined here, the index traits are implemented for
+//! some of the standard collections:
+//!
+//!  * `BTreeMap<K, Vec<R>>` implements [`Indexed`](crate::Indexed),
+//!    [`IndexedBorrow`](crate::IndexedBorrow),
+//!    [`IndexedCollection`](crate::IndexedCollection),
+//!    [`IndexedWithKeys`](crate::IndexedWithKeys),
+//!    [`MemoryFootprint`](crate::MemoryFootprint),
+//!    [`MergeableIndex`](crate::MergeableIndex),
+//!    [`OrderedIndex`](crate::OrderedIndex),
+//!    [`Prefetch`](crate::Prefetch),
+//!    [`SplittableIndex`](crate::SplittableIndex) and
+//!    [`TemporalIndex`](crate::TemporalIndex), as well as
+//!    [`PrefixIndex`](crate::PrefixIndex) for `String` keys.
+//!
+//! Looking up a key without records does not allocate:
+//!
+//! ```
+//! use std::alloc::GlobalAlloc;
+//! use std::alloc::Layout;
+//! use std::alloc::System;
+//! use std::collections::BTreeMap;
+//! use std::sync::atomic::AtomicUsize;
+//! use std::sync::atomic::Ordering;
+//!
+//! use ironsea_index::impls::MultiKeyIndex;
+//! use ironsea_index::impls::SortedVecIndex;
+//! use ironsea_index::Indexed;
+//! use ironsea_index::Record;
+//!
+//! struct Counting;
+//!
+//! static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+//!
+//! unsafe impl GlobalAlloc for Counting {
+//!     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+//!         ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+//!         System.alloc(layout)
+//!     }
+//!
+//!     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+//!         System.dealloc(ptr, layout)
+//!     }
+//! }
+//!
+//! #[global_allocator]
+//! static GLOBAL: Counting = Counting;
+//!
+//! struct Item(u32);
+//!
+//! impl Record<u32> for Item {
+//!     fn key(&self) -> u32 {
+//!         self.0
+//!     }
+//! }
+//!
+//! let sorted = SortedVecIndex::new(vec![Item(1), Item(3)]);
+//! let multi = MultiKeyIndex::new(vec![Item(1), Item(3)]);
+//! let map = vec![(1, vec![Item(1)]), (3, vec![])]
+//!     .into_iter()
+//!     .collect::<BTreeMap<_, _>>();
+//!
+//! let before = ALLOCATIONS.load(Ordering::SeqCst);
+//! assert_eq!(sorted.find(&2).count(), 0);
+//! assert_eq!(multi.find(&2).count(), 0);
+//! assert_eq!(map.find(&2).count(), 0);
+//! assert_eq!(map.find(&3).count(), 0);
+//! assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), before);
+//!
+//! // Hits do allocate the boxed iterator.
+//! assert_eq!(sorted.find(&1).count(), 1);
+//! assert!(ALLOCATIONS.load(Ordering::SeqCst) > before);
+//! ```
+
+mod btree_map;
+mod multi_key;
+mod sorted_interval;
+mod sorted_vec;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::Bound;
+use core::ops::RangeBounds;
+
+use crate::DefaultOrd;
+use crate::KeyOrd;
+
+pub use multi_key::MultiKeyIndex;
+pub use sorted_interval::SortedIntervalIndex;
+pub use sorted_vec::SortedVecEntry;
+pub use sorted_vec::SortedVecIndex;
+pub use sorted_vec::SortedVecOccupiedEntry;
+pub use sorted_vec::SortedVecVacantEntry;
+
+/// Check whether a range cannot contain any key.
+///
+/// This is the case when its start is greater than its end, or when both
+/// are equal and at least one of them is excluded.
+pub(crate) fn is_empty_range<K: Ord, B: RangeBounds<K>>(range: &B) -> bool {
+    is_empty_range_by(range, &DefaultOrd)
+}
+
+/// Check whether a range cannot contain any key, when the keys are
+/// ordered by `order`.
+pub(crate) fn is_empty_range_by<K, B, C>(range: &B, order: &C) -> bool
+where
+    B: RangeBounds<K>,
+    C: KeyOrd<K>,
+{
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(start), Bound::Included(end)) => {
+            order.cmp(start, end) == Ordering::Greater
+        }
+        (Bound::Included(start), Bound::Excluded(end))
+        | (Bound::Excluded(start), Bound::Included(end))
+        | (Bound::Excluded(start), Bound::Excluded(end)) => order.cmp(start, end) != Ordering::Less,
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+    }
+}
+
+/// Positions delimiting the items whose key is within `range`, in a
+/// slice of items sorted by key.
+///
+/// Only comparisons are used, so keys at the limits of their domain are
+/// handled like any other.
+pub(crate) fn range_positions<T, K, B, F>(items: &[T], key: F, range: &B) -> (usize, usize)
+where
+    K: Ord,
+    B: RangeBounds<K>,
+    F: Fn(&T) -> &K,
+{
+    range_positions_by(items, key, range, &DefaultOrd)
+}
+
+/// Positions delimiting the items whose key is within `range`, in a
+/// slice of items sorted by key according to `order`.
+pub(crate) fn range_positions_by<T, K, B, F, C>(
+    items: &[T],
+    key: F,
+    range: &B,
+    order: &C,
+) -> (usize, usize)
+where
+    B: RangeBounds<K>,
+    F: Fn(&T) -> &K,
+    C: KeyOrd<K>,
+{
+    if is_empty_range_by(range, order) {
+        return (0, 0);
+    }
+
+    let cmp = |item: &T, bound: &K| order.cmp(key(item), bound);
+
+    let start = match range.start_bound() {
+        Bound::Included(start) => items.partition_point(|i| cmp(i, start) == Ordering::Less),
+        Bound::Excluded(start) => items.partition_point(|i| cmp(i, start) != Ordering::Greater),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => items.partition_point(|i| cmp(i, end) != Ordering::Greater),
+        Bound::Excluded(end) => items.partition_point(|i| cmp(i, end) == Ordering::Less),
+        Bound::Unbounded => items.len(),
+    };
+
+    (start, end)
+}
+
+/// Merge two vectors sorted according to `cmp`, in O(n + m).
+///
+/// On ties, the items of `a` come first, so that the merge is stable.
+pub(crate) fn merge_sorted_by<T, F>(a: Vec<T>, b: Vec<T>, cmp: F) -> Vec<T>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let mut a = a.into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+
+    loop {
+        let item = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) if cmp(y, x) == Ordering::Less => b.next(),
+            (Some(_), _) => a.next(),
+            (None, _) => b.next(),
+        };
+
+        match item {
+            Some(item) => merged.push(item),
+            None => return merged,
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::iter;
+use core::mem;
+use core::ops::Bound;
+
+use super::is_empty_range;
+use crate::Indexed;
+use crate::IndexedBorrow;
+use crate::IndexedCollection;
+use crate::IndexedWithKeys;
+use crate::MemoryFootprint;
+use crate::MergeableIndex;
+use crate::OrderedIndex;
+use crate::Prefetch;
+use crate::PrefixIndex;
+use crate::Record;
+use crate::SplittableIndex;
+use crate::TemporalIndex;
+
+/// Sorted index over a map of keys to the records sharing that key.
+///
+/// Records sharing a key are returned in the order of their vector.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::Indexed;
+///
+/// let mut index = BTreeMap::new();
+/// index.insert(1, vec!["a", "b"]);
+/// index.insert(3, vec!["c"]);
+/// index.insert(5, vec!["d", "e"]);
+///
+/// assert_eq!(index.find(&1).collect::<Vec<_>>(), vec![&"a", &"b"]);
+/// assert_eq!(index.find(&2).count(), 0);
+///
+/// assert_eq!(
+///     index.find_range(&1, &3).collect::<Vec<_>>(),
+///     vec![&"a", &"b", &"c"]
+/// );
+/// assert_eq!(index.find_range_bounds(3..5).collect::<Vec<_>>(), vec![&"c"]);
+/// assert_eq!(index.find_range_bounds(..).count(), 5);
+/// assert_eq!(index.find_range(&5, &1).count(), 0);
+///
+/// assert_eq!(
+///     index.find_range_rev(&1, &3).collect::<Vec<_>>(),
+///     vec![&"c", &"b", &"a"]
+/// );
+///
+/// assert_eq!(index.count(&5), 2);
+///
+/// assert_eq!(index.range_bounds_records(&0, &9), Some((&"a", &"e")));
+/// assert_eq!(index.range_bounds_records(&3, &3), Some((&"c", &"c")));
+/// assert_eq!(index.range_bounds_records(&6, &9), None);
+///
+/// // Keys at the limits of their domain.
+/// let mut index = BTreeMap::new();
+/// index.insert(i64::MIN, vec!["min"]);
+/// index.insert(i64::MAX, vec!["max"]);
+///
+/// assert_eq!(index.find_range(&i64::MIN, &i64::MAX).count(), 2);
+/// assert_eq!(index.find_range(&i64::MAX, &i64::MIN).count(), 0);
+/// assert_eq!(index.find_range_bounds(i64::MIN..i64::MAX).count(), 1);
+/// assert_eq!(index.find_range_rev(&i64::MIN, &i64::MAX).count(), 2);
+/// ```
+impl<R, K> Indexed<R, K> for BTreeMap<K, Vec<R>>
+where
+    K: Ord,
+{
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        match self.get(key) {
+            Some(records) if !records.is_empty() => Box::new(records.iter()),
+            _ => Box::new(iter::empty()),
+        }
+    }
+
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        // BTreeMap::range panics on some of the empty ranges.
+        if is_empty_range::<K, _>(&(start, end)) {
+            return Box::new(iter::empty());
+        }
+
+        Box::new(
+            self.range((start, end))
+                .flat_map(|(_, records)| records.iter()),
+        )
+    }
+
+    fn count(&self, key: &K) -> usize {
+        self.get(key).map_or(0, Vec::len)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some_and(|records| !records.is_empty())
+    }
+
+    fn find_range_rev<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let range = (Bound::Included(start), Bound::Included(end));
+        if is_empty_range::<K, _>(&range) {
+            return Box::new(iter::empty());
+        }
+
+        Box::new(
+            self.range(range)
+                .rev()
+                .flat_map(|(_, records)| records.iter().rev()),
+        )
+    }
+
+    fn range_bounds_records<'i>(&'i self, start: &K, end: &K) -> Option<(&'i R, &'i R)> {
+        let range = (Bound::Included(start), Bound::Included(end));
+        if is_empty_range::<K, _>(&range) {
+            return None;
+        }
+
+        let mut records = self.range(range).filter(|(_, r)| !r.is_empty());
+        let (_, first) = records.next()?;
+        let last = records.next_back().map_or(first, |(_, r)| r);
+
+        Some((first.first()?, last.last()?))
+    }
+
+    fn find_range_dedup<'i>(&'i self, start: &K, end: &K) -> Vec<&'i R> {
+        // Each record is stored once, under a single key.
+        self.find_range(start, end).collect()
+    }
+}
+
+impl<R, K> IndexedBorrow<R, K> for BTreeMap<K, Vec<R>>
+where
+    K: Ord,
+{
+    fn find_borrowed<'i, Q>(&'i self, key: &Q) -> Box<dyn Iterator<Item = &'i R> + 'i>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Box::new(self.get(key).into_iter().flatten())
+    }
+}
+
+impl<R, K> IndexedWithKeys<R, K> for BTreeMap<K, Vec<R>>
+where
+    K: Ord,
+{
+    fn find_range_with_keys<'i>(&'i self, start: &K, end: &K) -> Vec<(&'i K, &'i R)> {
+        let range = (Bound::Included(start), Bound::Included(end));
+        if is_empty_range::<K, _>(&range) {
+            return Vec::new();
+        }
+
+        self.range(range)
+            .flat_map(|(key, records)| records.iter().map(move |record| (key, record)))
+            .collect()
+    }
+}
+
+/// As `BTreeMap` has inherent `insert`, `remove` and `retain` methods,
+/// these have to be called with the fully qualified syntax.
+///
+/// Keys left without records are removed from the map.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::Indexed;
+/// use ironsea_index::IndexedCollection;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Task(u32, &'static str);
+///
+/// impl Record<u32> for Task {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let mut index = BTreeMap::new();
+/// IndexedCollection::insert(&mut index, Task(1, "a"));
+/// IndexedCollection::insert(&mut index, Task(2, "b"));
+/// IndexedCollection::insert(&mut index, Task(1, "c"));
+/// assert_eq!(index.count(&1), 2);
+///
+/// IndexedCollection::insert(&mut index, Task(3, "d"));
+/// assert_eq!(index.drain_range(&3, &9), vec![Task(3, "d")]);
+/// assert!(!index.contains_key(&3));
+///
+/// assert!(index.update_key_by(&2, Task(4, "b"), |t| t.1 == "b"));
+/// assert!(!index.contains_key(&2));
+/// assert_eq!(index.find(&4).collect::<Vec<_>>(), vec![&Task(4, "b")]);
+/// assert!(index.update_key_by(&4, Task(2, "b"), |t| t.1 == "b"));
+///
+/// // Move the second record of key 1.
+/// assert!(index.update_key_by(&1, Task(5, "c"), |t| t.1 == "c"));
+/// assert_eq!(index.find(&1).collect::<Vec<_>>(), vec![&Task(1, "a")]);
+/// assert!(index.update_key_by(&5, Task(1, "c"), |t| t.1 == "c"));
+/// assert!(!index.update_key_by(&1, Task(5, "e"), |t| t.1 == "e"));
+///
+/// IndexedCollection::retain(&mut index, |t: &Task| t.1 != "b");
+/// assert!(!index.contains_key(&2));
+/// assert_eq!(index.len(), 1);
+///
+/// assert_eq!(
+///     IndexedCollection::remove(&mut index, &1),
+///     vec![Task(1, "a"), Task(1, "c")]
+/// );
+/// assert!(index.is_empty());
+/// ```
+impl<R, K> IndexedCollection<R, K> for BTreeMap<K, Vec<R>>
+where
+    R: Record<K>,
+    K: Ord,
+{
+    fn insert(&mut self, record: R) {
+        self.entry(record.key()).or_default().push(record);
+    }
+
+    fn remove(&mut self, key: &K) -> Vec<R> {
+        BTreeMap::remove(self, key).unwrap_or_default()
+    }
+
+    fn update_key_by<F>(&mut self, old_key: &K, record: R, mut is_same: F) -> bool
+    where
+        F: FnMut(&R) -> bool,
+    {
+        let records = match self.get_mut(old_key) {
+            Some(records) => records,
+            None => return false,
+        };
+        match records.iter().position(&mut is_same) {
+            Some(position) => {
+                records.remove(position);
+                if records.is_empty() {
+                    BTreeMap::remove(self, old_key);
+                }
+            }
+            None => return false,
+        }
+        IndexedCollection::insert(self, record);
+
+        true
+    }
+
+    fn retain<P: Fn(&R) -> bool>(&mut self, predicate: P) {
+        BTreeMap::retain(self, |_, records| {
+            records.retain(&predicate);
+            !records.is_empty()
+        });
+    }
+}
+
+/// Append the records of `other` to those of `self`, key by key, in
+/// O(m log(n + m)).
+impl<R, K> MergeableIndex for BTreeMap<K, Vec<R>>
+where
+    K: Ord,
+{
+    fn merge(mut self, other: Self) -> Self {
+        for (key, records) in other {
+            self.entry(key).or_default().extend(records);
+        }
+
+        self
+    }
+}
+
+/// The entries of the map are accounted for, but not the overhead of
+/// its nodes, which depends on how full they are.
+impl<R, K> MemoryFootprint for BTreeMap<K, Vec<R>> {
+    fn heap_bytes(&self) -> usize {
+        self.values()
+            .map(|records| {
+                mem::size_of::<K>()
+                    + mem::size_of::<Vec<R>>()
+                    + records.capacity() * mem::size_of::<R>()
+            })
+            .sum()
+    }
+}
+
+impl<R, K> Prefetch<K> for BTreeMap<K, Vec<R>> {}
+
+impl<R, K> SplittableIndex<K> for BTreeMap<K, Vec<R>>
+where
+    K: Ord,
+{
+    fn split_at_key(mut self, pivot: &K) -> (Self, Self) {
+        let right = self.split_off(pivot);
+
+        (self, right)
+    }
+}
+
+impl<R, K, V> TemporalIndex<R, K, V> for BTreeMap<K, Vec<R>>
+where
+    R: Record<V>,
+    K: Ord,
+    V: Ord,
+{
+}
+
+/// Keys without records are skipped, to match [`Indexed::find`].
+impl<R, K> OrderedIndex<R, K> for BTreeMap<K, Vec<R>>
+where
+    K: Ord,
+{
+    fn first_key(&self) -> Option<&K> {
+        self.iter().find(|(_, r)| !r.is_empty()).map(|(k, _)| k)
+    }
+
+    fn last_key(&self) -> Option<&K> {
+        self.iter()
+            .rev()
+            .find(|(_, r)| !r.is_empty())
+            .map(|(k, _)| k)
+    }
+
+    fn min(&self) -> Option<&R> {
+        self.values().flatten().next()
+    }
+
+    fn max(&self) -> Option<&R> {
+        self.values().rev().find_map(|r| r.first())
+    }
+}
+
+/// Prefix search over a map of `String` keys.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::PrefixIndex;
+///
+/// let mut index = BTreeMap::new();
+/// index.insert("apple".to_string(), vec![1]);
+/// index.insert("apricot".to_string(), vec![2, 3]);
+/// index.insert("banana".to_string(), vec![4]);
+///
+/// assert_eq!(index.find_prefix("ap").collect::<Vec<_>>(), vec![&1, &2, &3]);
+/// assert_eq!(index.find_prefix("c").count(), 0);
+/// ```
+impl<R> PrefixIndex<R> for BTreeMap<String, Vec<R>> {
+    fn find_prefix<'i>(&'i self, prefix: &str) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let prefix = prefix.to_string();
+        let range = self.range::<str, _>((Bound::Included(prefix.as_str()), Bound::Unbounded));
+
+        Box::new(
+            range
+                .take_while(move |(k, _)| k.starts_with(&prefix))
+                .flat_map(|(_, records)| records.iter()),
+        )
+    }
+}
@@ -0,0 +1,412 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::iter;
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::Bound;
+use core::ops::RangeBounds;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use super::merge_sorted_by;
+use super::range_positions;
+use crate::Indexed;
+use crate::IndexedWithKeys;
+use crate::InvariantError;
+use crate::MapConvertible;
+use crate::MemoryFootprint;
+use crate::MergeableIndex;
+use crate::PositionalIndex;
+use crate::Prefetch;
+use crate::RecordKeys;
+use crate::SizedIndex;
+
+/// Index storing each record under every key returned by its
+/// [`RecordKeys`] implementation.
+///
+/// The records are stored once, while the keys are kept sorted alongside
+/// the position of their record. A record is returned once for every
+/// matching key, so a range query spanning multiple keys of the same
+/// record returns it multiple times.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::MultiKeyIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::RecordKeys;
+///
+/// struct Range(u32, u32);
+///
+/// impl RecordKeys<u32> for Range {
+///     fn keys(&self) -> Vec<u32> {
+///         (self.0..self.1).collect()
+///     }
+/// }
+///
+/// let index = MultiKeyIndex::new(vec![Range(0, 3), Range(2, 4)]);
+///
+/// assert_eq!(index.find(&2).count(), 2);
+/// assert_eq!(index.find(&3).map(|r| r.0).collect::<Vec<_>>(), vec![2]);
+///
+/// // `Range(0, 3)` matches under 1 and 2, `Range(2, 4)` under 2.
+/// assert_eq!(
+///     index.find_range(&1, &2).map(|r| r.0).collect::<Vec<_>>(),
+///     vec![0, 0, 2]
+/// );
+/// ```
+///
+/// Keys at the limits of their domain are handled like any other:
+///
+/// ```
+/// use ironsea_index::impls::MultiKeyIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::RecordKeys;
+///
+/// struct Bytes(Vec<u8>);
+///
+/// impl RecordKeys<u8> for Bytes {
+///     fn keys(&self) -> Vec<u8> {
+///         self.0.clone()
+///     }
+/// }
+///
+/// let index = MultiKeyIndex::new(vec![Bytes(vec![0, u8::MAX]), Bytes(vec![u8::MAX])]);
+///
+/// assert_eq!(index.find(&u8::MAX).count(), 2);
+/// assert_eq!(index.find_range(&0, &u8::MAX).count(), 3);
+/// assert_eq!(index.find_range_bounds(u8::MAX..).count(), 2);
+/// assert_eq!(index.find_range_bounds(..u8::MAX).count(), 1);
+/// assert_eq!(index.find_range_rev(&u8::MAX, &u8::MAX).count(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiKeyIndex<R, K> {
+    /// Keys in ascending order, with the position of their record.
+    entries: Vec<(K, usize)>,
+    records: Vec<R>,
+}
+
+impl<R, K> MultiKeyIndex<R, K>
+where
+    R: RecordKeys<K>,
+    K: Ord,
+{
+    /// Build the index, by sorting the keys returned for each record.
+    ///
+    /// Records sharing a key are kept in their original relative order.
+    pub fn new(records: Vec<R>) -> Self {
+        let mut entries = records
+            .iter()
+            .enumerate()
+            .flat_map(|(position, record)| {
+                record.keys().into_iter().map(move |key| (key, position))
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        MultiKeyIndex { entries, records }
+    }
+
+    /// Check that the keys are sorted, and that each record is stored
+    /// under exactly the keys returned by [`RecordKeys::keys`].
+    ///
+    /// This is in O(n * k), for k keys per record, and meant for
+    /// debugging, or for validating an index built from untrusted data.
+    pub fn verify_invariants(&self) -> Result<(), InvariantError> {
+        if let Some(position) = self.entries.windows(2).position(|w| w[0].0 > w[1].0) {
+            return Err(InvariantError::Unsorted { position });
+        }
+
+        let mut stored = vec![Vec::new(); self.records.len()];
+        for (key, position) in &self.entries {
+            match stored.get_mut(*position) {
+                Some(keys) => keys.push(key),
+                None => {
+                    return Err(InvariantError::UnknownRecord {
+                        position: *position,
+                    })
+                }
+            }
+        }
+
+        for (position, (record, stored)) in self.records.iter().zip(stored).enumerate() {
+            let mut keys = record.keys();
+            keys.sort();
+            if !keys.iter().eq(stored) {
+                return Err(InvariantError::KeyMismatch { position });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Positions delimiting the entries whose key is within `range`.
+    fn positions<B: RangeBounds<K>>(&self, range: &B) -> (usize, usize) {
+        range_positions(&self.entries, |(k, _)| k, range)
+    }
+}
+
+impl<R, K> MultiKeyIndex<R, K> {
+    /// Number of records the index can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.records.capacity()
+    }
+
+    /// Release the memory left unused.
+    ///
+    /// This only affects the memory used by the index, not the results
+    /// of its queries.
+    pub fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+        self.records.shrink_to_fit();
+    }
+}
+
+impl<R, K> Indexed<R, K> for MultiKeyIndex<R, K>
+where
+    R: RecordKeys<K>,
+    K: Ord,
+{
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.find_bounds(Bound::Included(key), Bound::Included(key))
+    }
+
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let (start, end) = self.positions(&(start, end));
+        if start == end {
+            return Box::new(iter::empty());
+        }
+
+        Box::new(
+            self.entries[start..end]
+                .iter()
+                .map(move |(_, position)| &self.records[*position]),
+        )
+    }
+
+    fn count(&self, key: &K) -> usize {
+        let (start, end) = self.positions(&(Bound::Included(key), Bound::Included(key)));
+
+        end - start
+    }
+
+    fn find_range_rev<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+        if start == end {
+            return Box::new(iter::empty());
+        }
+
+        Box::new(
+            self.entries[start..end]
+                .iter()
+                .rev()
+                .map(move |(_, position)| &self.records[*position]),
+        )
+    }
+
+    fn find_range_dedup<'i>(&'i self, start: &K, end: &K) -> Vec<&'i R> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+        let mut seen = BTreeSet::new();
+
+        self.entries[start..end]
+            .iter()
+            .filter(|(_, position)| seen.insert(*position))
+            .map(|(_, position)| &self.records[*position])
+            .collect()
+    }
+}
+
+impl<R, K> IndexedWithKeys<R, K> for MultiKeyIndex<R, K>
+where
+    R: RecordKeys<K>,
+    K: Ord,
+{
+    fn find_range_with_keys<'i>(&'i self, start: &K, end: &K) -> Vec<(&'i K, &'i R)> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        self.entries[start..end]
+            .iter()
+            .map(|(key, position)| (key, &self.records[*position]))
+            .collect()
+    }
+}
+
+/// Positions refer to the records as provided to
+/// [`new`](MultiKeyIndex::new). After a [merge](MergeableIndex::merge),
+/// the records of the second index follow those of the first.
+impl<R, K> PositionalIndex<K> for MultiKeyIndex<R, K>
+where
+    R: RecordKeys<K>,
+    K: Ord,
+{
+    fn find_positions(&self, key: &K) -> Vec<usize> {
+        self.find_range_positions(key, key)
+    }
+
+    fn find_range_positions(&self, start: &K, end: &K) -> Vec<usize> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        self.entries[start..end]
+            .iter()
+            .map(|(_, position)| *position)
+            .collect()
+    }
+}
+
+/// Consume the index, returning each of its records once.
+///
+/// As records can have multiple keys, they are not returned in key
+/// order, and the order in which they are returned is unspecified.
+impl<R, K> IntoIterator for MultiKeyIndex<R, K> {
+    type Item = R;
+    type IntoIter = vec::IntoIter<R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.into_iter()
+    }
+}
+
+/// Build the index from an iterator of records, as
+/// [`new`](MultiKeyIndex::new) does.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::MultiKeyIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::RecordKeys;
+///
+/// struct Tags(&'static str, Vec<u32>);
+///
+/// impl RecordKeys<u32> for Tags {
+///     fn keys(&self) -> Vec<u32> {
+///         self.1.clone()
+///     }
+/// }
+///
+/// let index = vec![Tags("a", vec![1, 2]), Tags("b", vec![2])]
+///     .into_iter()
+///     .collect::<MultiKeyIndex<_, _>>();
+///
+/// assert_eq!(index.find(&2).map(|t| t.0).collect::<Vec<_>>(), vec!["a", "b"]);
+/// ```
+impl<R, K> FromIterator<R> for MultiKeyIndex<R, K>
+where
+    R: RecordKeys<K>,
+    K: Ord,
+{
+    fn from_iter<T: IntoIterator<Item = R>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Merge the sorted keys of both indices, in O(n + m).
+impl<R, K> MergeableIndex for MultiKeyIndex<R, K>
+where
+    K: Ord,
+{
+    fn merge(mut self, other: Self) -> Self {
+        let offset = self.records.len();
+        let entries = other
+            .entries
+            .into_iter()
+            .map(|(key, position)| (key, position + offset))
+            .collect();
+
+        self.entries = merge_sorted_by(self.entries, entries, |a, b| a.0.cmp(&b.0));
+        self.records.extend(other.records);
+
+        self
+    }
+}
+
+/// Two indices are equal when they hold the same records, in the same
+/// order, under the same keys.
+///
+/// The comparison is sensitive to the order the records were provided
+/// in, as it determines the order of the records sharing a key.
+impl<R, K> PartialEq for MultiKeyIndex<R, K>
+where
+    R: PartialEq,
+    K: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries && self.records == other.records
+    }
+}
+
+impl<R: Eq, K: Eq> Eq for MultiKeyIndex<R, K> {}
+
+/// Create an empty index.
+impl<R, K> Default for MultiKeyIndex<R, K> {
+    fn default() -> Self {
+        MultiKeyIndex {
+            entries: Vec::new(),
+            records: Vec::new(),
+        }
+    }
+}
+
+impl<R, K> MapConvertible<R, K> for MultiKeyIndex<R, K> {
+    fn to_btreemap(&self) -> BTreeMap<K, Vec<R>>
+    where
+        K: Ord + Clone,
+        R: Clone,
+    {
+        let mut map = BTreeMap::<_, Vec<_>>::new();
+        for (key, record) in self
+            .entries
+            .iter()
+            .map(|(key, position)| (key, &self.records[*position]))
+        {
+            map.entry(key.clone()).or_default().push(record.clone());
+        }
+
+        map
+    }
+
+    #[cfg(feature = "std")]
+    fn to_hashmap(&self) -> HashMap<K, Vec<R>>
+    where
+        K: Hash + Eq + Clone,
+        R: Clone,
+    {
+        let mut map = HashMap::<_, Vec<_>>::new();
+        for (key, record) in self
+            .entries
+            .iter()
+            .map(|(key, position)| (key, &self.records[*position]))
+        {
+            map.entry(key.clone()).or_default().push(record.clone());
+        }
+
+        map
+    }
+}
+
+impl<R, K> MemoryFootprint for MultiKeyIndex<R, K> {
+    fn heap_bytes(&self) -> usize {
+        self.entries.capacity() * mem::size_of::<(K, usize)>()
+            + self.records.capacity() * mem::size_of::<R>()
+    }
+}
+
+impl<R, K> Prefetch<K> for MultiKeyIndex<R, K> {}
+
+impl<R, K> SizedIndex for MultiKeyIndex<R, K> {
+    fn len(&self) -> usize {
+        self.records.len()
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::IntervalIndex;
+use crate::MemoryFootprint;
+use crate::Prefetch;
+use crate::RecordInterval;
+use crate::SizedIndex;
+
+/// Index of records spanning a range of keys, answering overlap queries.
+///
+/// The records are sorted by the lower bound of their interval, along
+/// with the greatest upper bound found up to each of them. A query
+/// therefore only considers the records starting before its end, from
+/// the first one preceded by an interval reaching its start, which are
+/// found with two binary searches. Records are returned in the order of
+/// their lower bound, records sharing it keeping their relative order.
+///
+/// This is efficient when the intervals have similar lengths. A few long
+/// intervals starting early force queries to scan all the records which
+/// follow them.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// See [`IntervalIndex`] for an example.
+#[derive(Clone, Debug)]
+pub struct SortedIntervalIndex<R, K> {
+    /// Intervals of the records, sorted by their lower bound.
+    intervals: Vec<(K, K)>,
+    /// Greatest upper bound of the intervals, up to each position.
+    max_hi: Vec<K>,
+    records: Vec<R>,
+}
+
+impl<R, K> SortedIntervalIndex<R, K>
+where
+    R: RecordInterval<K>,
+    K: Ord + Clone,
+{
+    /// Build the index, by sorting the records using the lower bound of
+    /// their [`RecordInterval::interval`].
+    ///
+    /// The sort is stable, so records sharing a lower bound keep their
+    /// relative order.
+    pub fn new(records: Vec<R>) -> Self {
+        let mut pairs = records
+            .into_iter()
+            .map(|record| (record.interval(), record))
+            .collect::<Vec<_>>();
+        pairs.sort_by(|a, b| (a.0).0.cmp(&(b.0).0));
+
+        let (intervals, records): (Vec<_>, Vec<_>) = pairs.into_iter().unzip();
+
+        let mut max_hi = Vec::<K>::with_capacity(intervals.len());
+        for (_, hi) in &intervals {
+            let max = match max_hi.last() {
+                Some(max) if max > hi => max.clone(),
+                _ => hi.clone(),
+            };
+            max_hi.push(max);
+        }
+
+        SortedIntervalIndex {
+            intervals,
+            max_hi,
+            records,
+        }
+    }
+}
+
+impl<R, K> IntervalIndex<R, K> for SortedIntervalIndex<R, K>
+where
+    K: Ord,
+{
+    fn find_overlapping(&self, start: &K, end: &K) -> Vec<&R> {
+        if start > end {
+            return Vec::new();
+        }
+
+        let last = self.intervals.partition_point(|(lo, _)| lo <= end);
+        let first = self.max_hi[..last].partition_point(|hi| hi < start);
+
+        self.intervals[first..last]
+            .iter()
+            .zip(&self.records[first..last])
+            .filter(|((lo, hi), _)| lo <= hi && hi >= start)
+            .map(|(_, record)| record)
+            .collect()
+    }
+}
+
+impl<R, K> SizedIndex for SortedIntervalIndex<R, K> {
+    fn len(&self) -> usize {
+        self.records.len()
+    }
+}
+
+impl<R, K> MemoryFootprint for SortedIntervalIndex<R, K> {
+    fn heap_bytes(&self) -> usize {
+        self.intervals.capacity() * mem::size_of::<(K, K)>()
+            + self.max_hi.capacity() * mem::size_of::<K>()
+            + self.records.capacity() * mem::size_of::<R>()
+    }
+}
+
+impl<R, K> Prefetch<K> for SortedIntervalIndex<R, K> {}
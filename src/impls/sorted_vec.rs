@@ -0,0 +1,1188 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::iter;
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::Bound;
+use core::ops::RangeBounds;
+use core::slice;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use super::merge_sorted_by;
+use super::range_positions;
+use super::range_positions_by;
+use crate::equi_depth_buckets;
+use crate::AsRecordSlice;
+use crate::ContiguousIndex;
+use crate::DefaultOrd;
+use crate::FuzzyIndex;
+use crate::Indexed;
+use crate::IndexedBorrow;
+use crate::IndexedCollection;
+use crate::IndexedMut;
+use crate::IndexedWithKeys;
+use crate::InvariantError;
+use crate::KeyEnumerable;
+use crate::KeyOrd;
+use crate::MapConvertible;
+use crate::MemoryFootprint;
+use crate::MergeableIndex;
+use crate::OrderedIndex;
+use crate::Prefetch;
+use crate::PrefixIndex;
+use crate::Record;
+use crate::SizedIndex;
+use crate::SplittableIndex;
+use crate::Statistics;
+use crate::TemporalIndex;
+
+/// Index over a vector of records sorted by key.
+///
+/// The keys are extracted once, when the index is built, and looked up
+/// with binary searches. Records sharing a key are stored contiguously,
+/// in the order they were provided.
+///
+/// The keys are sorted according to their `Ord` implementation, unless
+/// another order is given with
+/// [`with_order`](SortedVecIndex::with_order).
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///  * `C`: Order of the keys, see [`KeyOrd`]
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct MyPair {
+///     a: i64,
+///     b: i64,
+/// }
+///
+/// impl Record<i64> for MyPair {
+///     fn key(&self) -> i64 {
+///         self.a
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![
+///     MyPair { a: 3, b: 30 },
+///     MyPair { a: 1, b: 10 },
+///     MyPair { a: 3, b: 31 },
+///     MyPair { a: 2, b: 20 },
+///     MyPair { a: 3, b: 32 },
+/// ]);
+///
+/// // Records of duplicate keys are contiguous, in their original order.
+/// assert_eq!(
+///     index.find(&3).map(|r| r.b).collect::<Vec<_>>(),
+///     vec![30, 31, 32]
+/// );
+/// assert_eq!(index.find(&4).count(), 0);
+///
+/// assert_eq!(
+///     index.find_range(&2, &3).map(|r| r.b).collect::<Vec<_>>(),
+///     vec![20, 30, 31, 32]
+/// );
+/// assert_eq!(
+///     index.find_range_bounds(..3).map(|r| r.b).collect::<Vec<_>>(),
+///     vec![10, 20]
+/// );
+/// assert_eq!(index.count(&3), 3);
+///
+/// let found = index.find_many(&[3, 0, 1, 3]);
+/// let found = found
+///     .iter()
+///     .map(|(k, records)| (**k, records.iter().map(|r| r.b).collect::<Vec<_>>()))
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     found,
+///     vec![
+///         (3, vec![30, 31, 32]),
+///         (0, vec![]),
+///         (1, vec![10]),
+///         (3, vec![30, 31, 32]),
+///     ]
+/// );
+/// ```
+///
+/// Bounds are only compared to the keys, so keys at the limits of their
+/// domain neither overflow nor wrap around:
+///
+/// ```
+/// use std::ops::Bound;
+///
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// struct Item(i64);
+///
+/// impl Record<i64> for Item {
+///     fn key(&self) -> i64 {
+///         self.0
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![Item(i64::MAX), Item(0), Item(i64::MIN)]);
+///
+/// assert_eq!(index.find_range(&i64::MIN, &i64::MAX).count(), 3);
+/// assert_eq!(index.find_range(&i64::MAX, &i64::MAX).count(), 1);
+/// assert_eq!(index.find_range(&i64::MAX, &i64::MIN).count(), 0);
+/// assert_eq!(index.find_range_bounds(i64::MIN..i64::MAX).count(), 2);
+/// assert_eq!(
+///     index
+///         .find_range_bounds((Bound::Excluded(i64::MAX), Bound::Unbounded))
+///         .count(),
+///     0
+/// );
+/// assert_eq!(
+///     index
+///         .find_range_bounds((Bound::Unbounded, Bound::Excluded(i64::MIN)))
+///         .count(),
+///     0
+/// );
+/// assert_eq!(index.count_range(&i64::MIN, &i64::MAX), 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SortedVecIndex<R, K, C = DefaultOrd> {
+    keys: Vec<K>,
+    records: Vec<R>,
+    order: C,
+}
+
+impl<R, K> SortedVecIndex<R, K>
+where
+    R: Record<K>,
+    K: Ord,
+{
+    /// Build the index, by sorting the records using their
+    /// [`Record::key`] implementation.
+    ///
+    /// The sort is stable, so records sharing a key keep their relative
+    /// order.
+    pub fn new(records: Vec<R>) -> Self {
+        Self::with_order(records, DefaultOrd)
+    }
+}
+
+impl<R, K, C> SortedVecIndex<R, K, C>
+where
+    R: Record<K>,
+    C: KeyOrd<K>,
+{
+    /// Build the index, by sorting the records using their
+    /// [`Record::key`] implementation, in the given `order`.
+    ///
+    /// The sort is stable, so records sharing a key keep their relative
+    /// order.
+    ///
+    /// See [`KeyOrd`] for an example.
+    pub fn with_order(records: Vec<R>, order: C) -> Self {
+        let (keys, records) = sort(records, &order);
+
+        SortedVecIndex {
+            keys,
+            records,
+            order,
+        }
+    }
+
+    /// Retrieve all records matching the key, as a slice iterator.
+    ///
+    /// Unlike [`Indexed::find`], the iterator is not boxed, so this
+    /// never allocates, and a key without records returns an empty
+    /// iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Item(u32, char);
+    ///
+    /// impl Record<u32> for Item {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new(vec![Item(1, 'a'), Item(2, 'b'), Item(1, 'c')]);
+    ///
+    /// assert_eq!(index.find_or_empty(&1).map(|r| r.1).collect::<String>(), "ac");
+    /// assert_eq!(index.find_or_empty(&3).as_slice().len(), 0);
+    /// ```
+    pub fn find_or_empty(&self, key: &K) -> slice::Iter<'_, R> {
+        let (start, end) = self.positions(&(Bound::Included(key), Bound::Included(key)));
+
+        self.records[start..end].iter()
+    }
+
+    /// Retrieve the entry of the index for `key`, to modify its records
+    /// or insert one if there are none.
+    ///
+    /// The positions of the records of the key are looked up once, when
+    /// the entry is created, and reused by the methods of the entry, so
+    /// conditionally updating or inserting a record searches the index
+    /// only once. The key of an inserted record is checked against the
+    /// key of the entry in debug builds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecEntry;
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::Record;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Counter {
+    ///     word: &'static str,
+    ///     count: usize,
+    /// }
+    ///
+    /// impl Record<&'static str> for Counter {
+    ///     fn key(&self) -> &'static str {
+    ///         self.word
+    ///     }
+    /// }
+    ///
+    /// let mut index = SortedVecIndex::<Counter, _>::new(vec![]);
+    /// for word in "a rose is a rose is a rose".split(' ') {
+    ///     index
+    ///         .entry(word)
+    ///         .and_modify(|c| c.count += 1)
+    ///         .or_insert(Counter { word, count: 1 });
+    /// }
+    ///
+    /// let counts = index.find_range(&"a", &"z").map(|c| c.count).collect::<Vec<_>>();
+    /// assert_eq!(counts, vec![3, 2, 3]);
+    ///
+    /// match index.entry("rose") {
+    ///     SortedVecEntry::Occupied(entry) => {
+    ///         assert_eq!(entry.get().count, 3);
+    ///         assert_eq!(entry.remove(), vec![Counter { word: "rose", count: 3 }]);
+    ///     }
+    ///     SortedVecEntry::Vacant(_) => unreachable!(),
+    /// }
+    /// assert!(!index.contains_key(&"rose"));
+    /// ```
+    pub fn entry(&mut self, key: K) -> SortedVecEntry<'_, R, K, C> {
+        let (start, end) = self.positions(&(Bound::Included(&key), Bound::Included(&key)));
+
+        if start == end {
+            SortedVecEntry::Vacant(SortedVecVacantEntry {
+                index: self,
+                key,
+                position: start,
+            })
+        } else {
+            SortedVecEntry::Occupied(SortedVecOccupiedEntry {
+                index: self,
+                key,
+                start,
+                end,
+            })
+        }
+    }
+
+    /// Check that the keys are sorted, and that each record is stored
+    /// under the key returned by [`Record::key`].
+    ///
+    /// This is in O(n), and meant for debugging, or for validating an
+    /// index built from untrusted data, for example after
+    /// deserialisation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::IndexedMut;
+    /// use ironsea_index::InvariantError;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Item(u32);
+    ///
+    /// impl Record<u32> for Item {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let mut index = SortedVecIndex::new(vec![Item(3), Item(1), Item(2)]);
+    /// assert_eq!(index.verify_invariants(), Ok(()));
+    ///
+    /// // Modifying the key of a record breaks the index.
+    /// index.find_mut(&2).for_each(|r| r.0 = 7);
+    /// assert_eq!(
+    ///     index.verify_invariants(),
+    ///     Err(InvariantError::KeyMismatch { position: 1 })
+    /// );
+    /// ```
+    pub fn verify_invariants(&self) -> Result<(), InvariantError>
+    where
+        K: PartialEq,
+    {
+        if self.keys.len() != self.records.len() {
+            return Err(InvariantError::LengthMismatch {
+                keys: self.keys.len(),
+                records: self.records.len(),
+            });
+        }
+
+        if let Some(position) = self
+            .keys
+            .windows(2)
+            .position(|w| self.order.cmp(&w[0], &w[1]) == Ordering::Greater)
+        {
+            return Err(InvariantError::Unsorted { position });
+        }
+
+        match self
+            .keys
+            .iter()
+            .zip(&self.records)
+            .position(|(key, record)| record.key() != *key)
+        {
+            Some(position) => Err(InvariantError::KeyMismatch { position }),
+            None => Ok(()),
+        }
+    }
+
+    /// Positions delimiting the records whose key is within `range`.
+    fn positions<B: RangeBounds<K>>(&self, range: &B) -> (usize, usize) {
+        range_positions_by(&self.keys, |k| k, range, &self.order)
+    }
+}
+
+impl<R, K, C> SortedVecIndex<R, K, C> {
+    /// Number of records the index can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.records.capacity()
+    }
+
+    /// Release the memory left unused, for example after removing
+    /// records.
+    ///
+    /// This only affects the memory used by the index, not the results
+    /// of its queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::IndexedCollection;
+    /// use ironsea_index::Record;
+    /// use ironsea_index::SizedIndex;
+    ///
+    /// struct Item(u32);
+    ///
+    /// impl Record<u32> for Item {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let mut index = SortedVecIndex::new((0..1000).map(Item).collect());
+    /// index.retain(|r| r.0 % 100 == 0);
+    ///
+    /// let before = index.find_range(&0, &999).map(|r| r.0).collect::<Vec<_>>();
+    /// let capacity = index.capacity();
+    ///
+    /// index.shrink_to_fit();
+    ///
+    /// assert!(index.capacity() < capacity);
+    /// assert_eq!(index.len(), 10);
+    /// assert_eq!(index.find_range(&0, &999).map(|r| r.0).collect::<Vec<_>>(), before);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.keys.shrink_to_fit();
+        self.records.shrink_to_fit();
+    }
+}
+
+/// Entry of a [`SortedVecIndex`] for a key, which may or may not have
+/// records.
+///
+/// This is returned by [`SortedVecIndex::entry`], and keeps the
+/// positions of the records of the key.
+pub enum SortedVecEntry<'i, R, K, C = DefaultOrd> {
+    /// At least one record is stored under the key.
+    Occupied(SortedVecOccupiedEntry<'i, R, K, C>),
+
+    /// No record is stored under the key.
+    Vacant(SortedVecVacantEntry<'i, R, K, C>),
+}
+
+impl<'i, R, K, C> SortedVecEntry<'i, R, K, C>
+where
+    R: Record<K>,
+{
+    /// Retrieve the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            SortedVecEntry::Occupied(entry) => entry.key(),
+            SortedVecEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Modify in place all the records stored under the key, if any.
+    ///
+    /// The same warning as for [`IndexedMut`] applies: the fields
+    /// participating in the key of the records must not be modified.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnMut(&mut R),
+    {
+        match self {
+            SortedVecEntry::Occupied(entry) => {
+                entry.index.records[entry.start..entry.end]
+                    .iter_mut()
+                    .for_each(f);
+                SortedVecEntry::Occupied(entry)
+            }
+            SortedVecEntry::Vacant(entry) => SortedVecEntry::Vacant(entry),
+        }
+    }
+
+    /// Insert `record` if no record is stored under the key, and
+    /// retrieve the first record stored under the key.
+    ///
+    /// The key of `record` must be the key of the entry, which is
+    /// checked in debug builds.
+    pub fn or_insert(self, record: R) -> &'i mut R
+    where
+        K: PartialEq,
+    {
+        match self {
+            SortedVecEntry::Occupied(entry) => entry.into_mut(),
+            SortedVecEntry::Vacant(entry) => entry.insert(record),
+        }
+    }
+
+    /// Insert the record returned by `f` if no record is stored under
+    /// the key, calling `f` only in that case, and retrieve the first
+    /// record stored under the key.
+    ///
+    /// The key of the record must be the key of the entry, which is
+    /// checked in debug builds.
+    pub fn or_insert_with<F>(self, f: F) -> &'i mut R
+    where
+        K: PartialEq,
+        F: FnOnce() -> R,
+    {
+        match self {
+            SortedVecEntry::Occupied(entry) => entry.into_mut(),
+            SortedVecEntry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+}
+
+/// Entry of a [`SortedVecIndex`] for a key with at least one record.
+///
+/// This is part of [`SortedVecEntry`].
+pub struct SortedVecOccupiedEntry<'i, R, K, C = DefaultOrd> {
+    index: &'i mut SortedVecIndex<R, K, C>,
+    key: K,
+    start: usize,
+    end: usize,
+}
+
+impl<'i, R, K, C> SortedVecOccupiedEntry<'i, R, K, C> {
+    /// Retrieve the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Retrieve the first record stored under the key.
+    pub fn get(&self) -> &R {
+        &self.index.records[self.start]
+    }
+
+    /// Retrieve all the records stored under the key.
+    pub fn records(&self) -> &[R] {
+        &self.index.records[self.start..self.end]
+    }
+
+    /// Retrieve the first record stored under the key, giving up the
+    /// entry.
+    pub fn into_mut(self) -> &'i mut R {
+        &mut self.index.records[self.start]
+    }
+
+    /// Remove all the records stored under the key, in the order
+    /// returned by [`Indexed::find`].
+    pub fn remove(self) -> Vec<R> {
+        self.index.keys.drain(self.start..self.end);
+        self.index.records.drain(self.start..self.end).collect()
+    }
+}
+
+/// Entry of a [`SortedVecIndex`] for a key without records.
+///
+/// This is part of [`SortedVecEntry`].
+pub struct SortedVecVacantEntry<'i, R, K, C = DefaultOrd> {
+    index: &'i mut SortedVecIndex<R, K, C>,
+    key: K,
+    position: usize,
+}
+
+impl<'i, R, K, C> SortedVecVacantEntry<'i, R, K, C>
+where
+    R: Record<K>,
+{
+    /// Retrieve the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Retrieve the key of the entry, giving up the entry.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Insert `record` where its key belongs, without looking it up
+    /// again, and retrieve it.
+    ///
+    /// The key of `record` must be the key of the entry, which is
+    /// checked in debug builds.
+    pub fn insert(self, record: R) -> &'i mut R
+    where
+        K: PartialEq,
+    {
+        debug_assert!(
+            record.key() == self.key,
+            "the key of the record must be the key of the entry"
+        );
+
+        self.index.keys.insert(self.position, self.key);
+        self.index.records.insert(self.position, record);
+
+        &mut self.index.records[self.position]
+    }
+}
+
+/// Sort `records` by key in the given `order`, returning the keys and the
+/// records in that order.
+fn sort<R, K, C>(records: Vec<R>, order: &C) -> (Vec<K>, Vec<R>)
+where
+    R: Record<K>,
+    C: KeyOrd<K>,
+{
+    let mut pairs = records
+        .into_iter()
+        .map(|record| (record.key(), record))
+        .collect::<Vec<_>>();
+    pairs.sort_by(|a, b| order.cmp(&a.0, &b.0));
+
+    pairs.into_iter().unzip()
+}
+
+impl<R, K, C> Indexed<R, K> for SortedVecIndex<R, K, C>
+where
+    R: Record<K>,
+    C: KeyOrd<K>,
+{
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.find_bounds(Bound::Included(key), Bound::Included(key))
+    }
+
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let (start, end) = self.positions(&(start, end));
+        if start == end {
+            return Box::new(iter::empty());
+        }
+
+        Box::new(self.records[start..end].iter())
+    }
+
+    fn count(&self, key: &K) -> usize {
+        let (start, end) = self.positions(&(Bound::Included(key), Bound::Included(key)));
+
+        end - start
+    }
+
+    fn count_range(&self, start: &K, end: &K) -> usize {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        end - start
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.keys
+            .binary_search_by(|k| self.order.cmp(k, key))
+            .is_ok()
+    }
+
+    fn find_range_paged<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+        offset: usize,
+        limit: usize,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+        let start = start.saturating_add(offset).min(end);
+        let end = start.saturating_add(limit).min(end);
+
+        Box::new(self.records[start..end].iter())
+    }
+
+    fn find_many<'i>(&'i self, keys: &'i [K]) -> Vec<(&'i K, Vec<&'i R>)> {
+        // Look the keys up in ascending order, so that each search only
+        // has to consider the records following the previous match.
+        let mut sorted = (0..keys.len()).collect::<Vec<_>>();
+        sorted.sort_by(|a, b| self.order.cmp(&keys[*a], &keys[*b]));
+
+        let mut found = vec![(0, 0); keys.len()];
+        let mut from = 0;
+        for i in sorted {
+            let key = &keys[i];
+            let cmp = |k: &K| self.order.cmp(k, key);
+            let start = from + self.keys[from..].partition_point(|k| cmp(k) == Ordering::Less);
+            let end = start + self.keys[start..].partition_point(|k| cmp(k) != Ordering::Greater);
+
+            found[i] = (start, end);
+            from = start;
+        }
+
+        keys.iter()
+            .zip(found)
+            .map(|(key, (start, end))| (key, self.records[start..end].iter().collect()))
+            .collect()
+    }
+
+    fn find_range_rev<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        Box::new(self.records[start..end].iter().rev())
+    }
+
+    fn find_range_dedup<'i>(&'i self, start: &K, end: &K) -> Vec<&'i R> {
+        // Each record is stored once, under a single key.
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        self.records[start..end].iter().collect()
+    }
+
+    fn range_bounds_records<'i>(&'i self, start: &K, end: &K) -> Option<(&'i R, &'i R)> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+        if start == end {
+            return None;
+        }
+
+        Some((&self.records[start], &self.records[end - 1]))
+    }
+}
+
+impl<R, K> IndexedBorrow<R, K> for SortedVecIndex<R, K>
+where
+    R: Record<K>,
+    K: Ord,
+{
+    fn find_borrowed<'i, Q>(&'i self, key: &Q) -> Box<dyn Iterator<Item = &'i R> + 'i>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let start = self.keys.partition_point(|k| k.borrow() < key);
+        let end = self.keys.partition_point(|k| k.borrow() <= key);
+
+        Box::new(self.records[start..end].iter())
+    }
+}
+
+impl<R, K, C> IndexedMut<R, K> for SortedVecIndex<R, K, C>
+where
+    R: Record<K>,
+    C: KeyOrd<K>,
+{
+    fn find_mut<'i>(&'i mut self, key: &K) -> Box<dyn Iterator<Item = &'i mut R> + 'i> {
+        let (start, end) = self.positions(&(Bound::Included(key), Bound::Included(key)));
+
+        Box::new(self.records[start..end].iter_mut())
+    }
+
+    fn find_range_mut<'i>(
+        &'i mut self,
+        start: &K,
+        end: &K,
+    ) -> Box<dyn Iterator<Item = &'i mut R> + 'i> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        Box::new(self.records[start..end].iter_mut())
+    }
+
+    fn reindex(&mut self) {
+        let records = mem::take(&mut self.records);
+        let (keys, records) = sort(records, &self.order);
+
+        self.keys = keys;
+        self.records = records;
+    }
+}
+
+/// Records are kept in a sorted vector, so inserting or removing records
+/// moves all the following ones, in O(n).
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::IndexedCollection;
+/// use ironsea_index::Record;
+/// use ironsea_index::SizedIndex;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Session {
+///     user: u32,
+///     expires: u64,
+/// }
+///
+/// impl Record<u32> for Session {
+///     fn key(&self) -> u32 {
+///         self.user
+///     }
+/// }
+///
+/// let mut index = SortedVecIndex::new(vec![
+///     Session { user: 1, expires: 100 },
+///     Session { user: 2, expires: 300 },
+/// ]);
+/// index.insert(Session { user: 1, expires: 200 });
+/// assert_eq!(index.count(&1), 2);
+///
+/// // Evict the sessions expired at time 150.
+/// index.retain(|s| s.expires > 150);
+/// assert_eq!(index.find(&1).collect::<Vec<_>>(), vec![&Session { user: 1, expires: 200 }]);
+/// assert_eq!(index.len(), 2);
+///
+/// assert_eq!(index.remove(&2), vec![Session { user: 2, expires: 300 }]);
+/// assert_eq!(index.len(), 1);
+/// ```
+impl<R, K, C> IndexedCollection<R, K> for SortedVecIndex<R, K, C>
+where
+    R: Record<K>,
+    C: KeyOrd<K>,
+{
+    fn insert(&mut self, record: R) {
+        let key = record.key();
+        let position = self
+            .keys
+            .partition_point(|k| self.order.cmp(k, &key) != Ordering::Greater);
+
+        self.keys.insert(position, key);
+        self.records.insert(position, record);
+    }
+
+    fn remove(&mut self, key: &K) -> Vec<R> {
+        let (start, end) = self.positions(&(Bound::Included(key), Bound::Included(key)));
+
+        self.keys.drain(start..end);
+        self.records.drain(start..end).collect()
+    }
+
+    fn drain_range(&mut self, start: &K, end: &K) -> Vec<R>
+    where
+        K: PartialEq,
+    {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        self.keys.drain(start..end);
+        self.records.drain(start..end).collect()
+    }
+
+    fn update_key_by<F>(&mut self, old_key: &K, record: R, mut is_same: F) -> bool
+    where
+        F: FnMut(&R) -> bool,
+    {
+        let (start, end) = self.positions(&(Bound::Included(old_key), Bound::Included(old_key)));
+        let position = match self.records[start..end].iter().position(&mut is_same) {
+            Some(offset) => start + offset,
+            None => return false,
+        };
+
+        self.keys.remove(position);
+        self.records.remove(position);
+        self.insert(record);
+
+        true
+    }
+
+    fn retain<P: Fn(&R) -> bool>(&mut self, predicate: P) {
+        let kept = self.records.iter().map(predicate).collect::<Vec<_>>();
+
+        let mut keep = kept.iter();
+        self.keys.retain(|_| *keep.next().unwrap());
+        let mut keep = kept.iter();
+        self.records.retain(|_| *keep.next().unwrap());
+    }
+}
+
+impl<R, K, C> ContiguousIndex<R, K> for SortedVecIndex<R, K, C>
+where
+    R: Record<K>,
+    C: KeyOrd<K>,
+{
+    fn find_range_slice(&self, start: &K, end: &K) -> Option<&[R]> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        Some(&self.records[start..end])
+    }
+
+    fn lower_bound(&self, key: &K) -> usize {
+        self.positions(&(Bound::Included(key), Bound::Unbounded)).0
+    }
+
+    fn upper_bound(&self, key: &K) -> usize {
+        self.positions(&(Bound::Unbounded, Bound::Included(key))).1
+    }
+}
+
+impl<R, K, C> AsRecordSlice<R> for SortedVecIndex<R, K, C> {
+    fn as_record_slice(&self) -> &[R] {
+        &self.records
+    }
+}
+
+impl<R, K, C> IndexedWithKeys<R, K> for SortedVecIndex<R, K, C>
+where
+    R: Record<K>,
+    C: KeyOrd<K>,
+{
+    fn find_range_with_keys<'i>(&'i self, start: &K, end: &K) -> Vec<(&'i K, &'i R)> {
+        let (start, end) = self.positions(&(Bound::Included(start), Bound::Included(end)));
+
+        self.keys[start..end]
+            .iter()
+            .zip(&self.records[start..end])
+            .collect()
+    }
+}
+
+/// Consume the index, returning its records in key order.
+///
+/// Records sharing a key are returned in the order they were provided.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![Item(2, 'a'), Item(1, 'b'), Item(2, 'c')]);
+///
+/// let records = index.into_iter().collect::<Vec<_>>();
+/// assert_eq!(records, vec![Item(1, 'b'), Item(2, 'a'), Item(2, 'c')]);
+/// ```
+impl<R, K, C> IntoIterator for SortedVecIndex<R, K, C> {
+    type Item = R;
+    type IntoIter = vec::IntoIter<R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.into_iter()
+    }
+}
+
+/// Build the index from an iterator of records, as
+/// [`with_order`](SortedVecIndex::with_order) does with the default
+/// value of the order.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let index: SortedVecIndex<_, _> = "hello"
+///     .chars()
+///     .zip((0..5).rev())
+///     .map(|(c, k)| Item(k, c))
+///     .collect();
+///
+/// assert_eq!(
+///     index.find_range(&0, &4).map(|r| r.1).collect::<String>(),
+///     "olleh"
+/// );
+/// ```
+impl<R, K, C> FromIterator<R> for SortedVecIndex<R, K, C>
+where
+    R: Record<K>,
+    C: KeyOrd<K> + Default,
+{
+    fn from_iter<T: IntoIterator<Item = R>>(iter: T) -> Self {
+        Self::with_order(iter.into_iter().collect(), C::default())
+    }
+}
+
+/// Merge the sorted records of both indices, in O(n + m).
+///
+/// The order of `self` is kept, and both indices must use the same
+/// order.
+impl<R, K, C> MergeableIndex for SortedVecIndex<R, K, C>
+where
+    C: KeyOrd<K>,
+{
+    fn merge(self, other: Self) -> Self {
+        let order = self.order;
+        let merged = merge_sorted_by(
+            self.keys.into_iter().zip(self.records).collect(),
+            other.keys.into_iter().zip(other.records).collect(),
+            |a: &(K, R), b: &(K, R)| order.cmp(&a.0, &b.0),
+        );
+        let (keys, records) = merged.into_iter().unzip();
+
+        SortedVecIndex {
+            keys,
+            records,
+            order,
+        }
+    }
+}
+
+/// Split the records at the position of `pivot`, found with a binary
+/// search, in O(n) as the right part is moved to a new vector.
+impl<R, K, C> SplittableIndex<K> for SortedVecIndex<R, K, C>
+where
+    C: KeyOrd<K> + Clone,
+{
+    fn split_at_key(mut self, pivot: &K) -> (Self, Self) {
+        let position = self
+            .keys
+            .partition_point(|k| self.order.cmp(k, pivot) == Ordering::Less);
+
+        let right = SortedVecIndex {
+            keys: self.keys.split_off(position),
+            records: self.records.split_off(position),
+            order: self.order.clone(),
+        };
+
+        (self, right)
+    }
+}
+
+/// Two indices are equal when they hold the same records under the same
+/// keys, in the same order.
+///
+/// As records sharing a key keep the order they were provided in, the
+/// comparison is sensitive to the relative order of those records. The
+/// order of the keys is not compared.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let a = SortedVecIndex::new(vec![Item(1, 'a'), Item(2, 'b'), Item(3, 'c')]);
+/// let b = SortedVecIndex::new(vec![Item(1, 'a'), Item(2, 'b'), Item(3, 'c')]);
+/// assert_eq!(a, b);
+///
+/// // Sorting makes the order of distinct keys irrelevant.
+/// let c = SortedVecIndex::new(vec![Item(3, 'c'), Item(1, 'a'), Item(2, 'b')]);
+/// assert_eq!(a, c);
+///
+/// // But not the order of the records sharing a key.
+/// let d = SortedVecIndex::new(vec![Item(1, 'a'), Item(1, 'b')]);
+/// let e = SortedVecIndex::new(vec![Item(1, 'b'), Item(1, 'a')]);
+/// assert_ne!(d, e);
+/// ```
+impl<R, K, C> PartialEq for SortedVecIndex<R, K, C>
+where
+    R: PartialEq,
+    K: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys && self.records == other.records
+    }
+}
+
+impl<R: Eq, K: Eq, C> Eq for SortedVecIndex<R, K, C> {}
+
+/// Create an empty index.
+impl<R, K, C: Default> Default for SortedVecIndex<R, K, C> {
+    fn default() -> Self {
+        SortedVecIndex {
+            keys: Vec::new(),
+            records: Vec::new(),
+            order: C::default(),
+        }
+    }
+}
+
+impl<R, K> OrderedIndex<R, K> for SortedVecIndex<R, K>
+where
+    R: Record<K>,
+    K: Ord,
+{
+    fn first_key(&self) -> Option<&K> {
+        self.keys.first()
+    }
+
+    fn last_key(&self) -> Option<&K> {
+        self.keys.last()
+    }
+
+    fn min(&self) -> Option<&R> {
+        self.records.first()
+    }
+}
+
+impl<R, K, C> MapConvertible<R, K> for SortedVecIndex<R, K, C> {
+    fn to_btreemap(&self) -> BTreeMap<K, Vec<R>>
+    where
+        K: Ord + Clone,
+        R: Clone,
+    {
+        let mut map = BTreeMap::<_, Vec<_>>::new();
+        for (key, record) in self.keys.iter().zip(&self.records) {
+            map.entry(key.clone()).or_default().push(record.clone());
+        }
+
+        map
+    }
+
+    #[cfg(feature = "std")]
+    fn to_hashmap(&self) -> HashMap<K, Vec<R>>
+    where
+        K: Hash + Eq + Clone,
+        R: Clone,
+    {
+        let mut map = HashMap::<_, Vec<_>>::new();
+        for (key, record) in self.keys.iter().zip(&self.records) {
+            map.entry(key.clone()).or_default().push(record.clone());
+        }
+
+        map
+    }
+}
+
+impl<R, K, C> MemoryFootprint for SortedVecIndex<R, K, C> {
+    fn heap_bytes(&self) -> usize {
+        self.keys.capacity() * mem::size_of::<K>() + self.records.capacity() * mem::size_of::<R>()
+    }
+}
+
+impl<R, K, C> Prefetch<K> for SortedVecIndex<R, K, C> {}
+
+impl<R, K, C> SizedIndex for SortedVecIndex<R, K, C> {
+    fn len(&self) -> usize {
+        self.records.len()
+    }
+}
+
+impl<R, K> KeyEnumerable<K> for SortedVecIndex<R, K> {
+    fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i K> + 'i> {
+        Box::new(self.keys.iter())
+    }
+
+    fn distinct_keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i K> + 'i>
+    where
+        K: Ord,
+    {
+        // Keys are sorted, so duplicates are next to each other.
+        Box::new(self.keys.chunk_by(|a, b| a == b).map(|keys| &keys[0]))
+    }
+
+    fn distinct_keys_in_range(&self, start: &K, end: &K) -> usize
+    where
+        K: Ord,
+    {
+        let range = (Bound::Included(start), Bound::Included(end));
+        let (start, end) = range_positions(&self.keys, |k| k, &range);
+
+        self.keys[start..end].chunk_by(|a, b| a == b).count()
+    }
+}
+
+impl<R, K> Statistics<K> for SortedVecIndex<R, K> {
+    fn key_histogram(&self, buckets: usize) -> Vec<(K, K, usize)>
+    where
+        K: Ord + Clone,
+    {
+        let counts = self
+            .keys
+            .chunk_by(|a, b| a == b)
+            .map(|keys| (&keys[0], keys.len()));
+
+        equi_depth_buckets(counts, self.keys.len(), buckets)
+    }
+
+    fn estimate_range_count(&self, start: &K, end: &K) -> usize
+    where
+        K: Ord,
+    {
+        let range = (Bound::Included(start), Bound::Included(end));
+        let (start, end) = range_positions(&self.keys, |k| k, &range);
+
+        end - start
+    }
+}
+
+impl<R, K, V> TemporalIndex<R, K, V> for SortedVecIndex<R, K>
+where
+    R: Record<K> + Record<V>,
+    K: Ord,
+    V: Ord,
+{
+}
+
+impl<R> FuzzyIndex<R> for SortedVecIndex<R, String> where R: Record<String> {}
+
+impl<R> PrefixIndex<R> for SortedVecIndex<R, String>
+where
+    R: Record<String>,
+{
+    fn find_prefix<'i>(&'i self, prefix: &str) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        let start = self.keys.partition_point(|k| k.as_str() < prefix);
+        let end = start + self.keys[start..].partition_point(|k| k.starts_with(prefix));
+
+        Box::new(self.records[start..end].iter())
+    }
+}
@@ -1,5 +1,6 @@
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
+#![no_std]
 
 //! # Iron Sea - Index
 //!
@@ -17,7 +18,58 @@
 //! This enables the index implementations to be agnostic from the
 //! underlying data structure, and re-used.
 //!
+//! ## Features
 //!
+//!  * `derive`: provides `#[derive(Record)]` and
+//!    `#[derive(RecordFields)]`, see
+//!    [`ironsea_index_derive`](https://docs.rs/ironsea_index_derive).
+//!  * `std` (enabled by default): link the standard library. Without
+//!    it the crate is `no_std`, and only requires `alloc`. Also
+//!    provides `adapters::CachingIndex`, `adapters::ConcurrentIndex`,
+//!    `dedup_by_key` and `dedup_keep_first`.
+//!  * `parallel`: provides `Indexed::par_find_range`, implies `std`.
+//!    This uses the scoped threads of the standard library: there is
+//!    no `rayon` feature, as the crate has no dependency besides
+//!    `ironsea_index_derive`.
+//!  * `testing`: provides the `testing` module, of property checks
+//!    for implementations of the traits.
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod adapters;
+pub mod impls;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::convert::Infallible;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::iter;
+use core::marker::PhantomData;
+use core::ops::Bound;
+use core::ops::ControlFlow;
+use core::ops::RangeBounds;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+#[cfg(feature = "derive")]
+pub use ironsea_index_derive::Record;
+#[cfg(feature = "derive")]
+pub use ironsea_index_derive::RecordFields;
 
 /// Record behavior used by Indexed implementations.
 ///
@@ -61,10 +113,9 @@
 /// // Example without using an actual index crate, we will simply use
 /// // the Record<K> trait to sort the array of pairs.
 /// let mut lex_sort = table.clone();
-/// lex_sort.sort_unstable_by_key(|e| {let k: String = e.key(); k});
+/// ironsea_index::sort_by_key::<_, String>(&mut lex_sort);
 ///
-/// let mut num_sort = table.clone();
-/// num_sort.sort_unstable_by_key(|e| {let k: i64 = e.key(); k});
+/// let num_sort = ironsea_index::sorted_by_key::<_, i64, _>(table.clone());
 ///
 /// assert_eq!(format!("unsorted {:?}", table),
 ///      "unsorted [MyPair { a: 10, b: 34 }, MyPair { a: 1, b: 56 }, MyPair { a: 2, b: 23 }]");
@@ -78,55 +129,5077 @@ pub trait Record<K> {
     fn key(&self) -> K;
 }
 
-/// Record behavior used by IndexedDestructured implementations.
+/// Records behind a reference have the key of the record they point to.
 ///
-/// RecordFields is used by indices which de-structure records into two
-/// components, the key and the fields associated to that unique key.
-/// This is provided by the users of indices, for each of their `struct`
-/// they wish to index.
-pub trait RecordFields<F> {
-    /// Extract the fields of the record
-    fn fields(&self) -> F;
+/// This allows indexing borrowed records, without moving or cloning
+/// them.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct MyPair {
+///     a: i64,
+///     b: i64,
+/// }
+///
+/// impl Record<i64> for MyPair {
+///     fn key(&self) -> i64 {
+///         self.a
+///     }
+/// }
+///
+/// let table = vec![MyPair { a: 2, b: 20 }, MyPair { a: 1, b: 10 }];
+/// let index = SortedVecIndex::new(table.iter().collect::<Vec<&MyPair>>());
+///
+/// assert_eq!(index.find(&1).collect::<Vec<_>>(), vec![&&table[1]]);
+/// ```
+impl<K, R> Record<K> for &R
+where
+    R: Record<K> + ?Sized,
+{
+    fn key(&self) -> K {
+        (**self).key()
+    }
 }
 
-/// Methods provided by indices.
+/// Boxed records have the key of the record they contain.
 ///
-/// This kind of indices can work on references to the original vector
-/// or take ownership of the records, based on the type given for the
-/// records.
+/// This allows indexing trait objects, as long as the trait has
+/// `Record` as a supertrait.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// trait Shape: Record<u32> {
+///     fn area(&self) -> f64;
+/// }
+///
+/// struct Square(u32, f64);
+///
+/// impl Record<u32> for Square {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// impl Shape for Square {
+///     fn area(&self) -> f64 {
+///         self.1 * self.1
+///     }
+/// }
+///
+/// let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square(2, 3.0)), Box::new(Square(1, 2.0))];
+/// let index = SortedVecIndex::new(shapes);
+///
+/// assert_eq!(index.find(&1).map(|s| s.area()).collect::<Vec<_>>(), vec![4.0]);
+/// ```
+impl<K, R> Record<K> for Box<R>
+where
+    R: Record<K> + ?Sized,
+{
+    fn key(&self) -> K {
+        (**self).key()
+    }
+}
+
+/// Sort `records` in place by their [`Record::key`].
+///
+/// The sort is stable, so records sharing a key keep their relative
+/// order. The key is extracted at every comparison, so when it is costly
+/// to compute, for example when it allocates, `slice::sort_by_cached_key`
+/// might be faster.
+///
+/// When the type of the records implements `Record` for multiple key
+/// types, the key type has to be specified, as in
+/// `sort_by_key::<_, u32>(&mut records)`.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Task(u32, &'static str);
+///
+/// impl Record<u32> for Task {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let mut tasks = vec![Task(2, "b"), Task(1, "a"), Task(2, "c")];
+/// ironsea_index::sort_by_key(&mut tasks);
+/// assert_eq!(tasks, vec![Task(1, "a"), Task(2, "b"), Task(2, "c")]);
+/// ```
+pub fn sort_by_key<R, K>(records: &mut [R])
+where
+    R: Record<K>,
+    K: Ord,
+{
+    records.sort_by_key(|r| r.key());
+}
+
+/// Sort `records` in place by their [`Record::key`], without preserving
+/// the relative order of the records sharing a key.
+///
+/// This is usually faster than [`sort_by_key`], and does not allocate.
+pub fn sort_unstable_by_key<R, K>(records: &mut [R])
+where
+    R: Record<K>,
+    K: Ord,
+{
+    records.sort_unstable_by_key(|r| r.key());
+}
+
+/// Collect `records` in a `Vec`, sorted by their [`Record::key`].
+///
+/// The sort is stable, see [`sort_by_key`].
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Record;
+///
+/// struct Task(u32, &'static str);
+///
+/// impl Record<u32> for Task {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let tasks = vec![Task(3, "c"), Task(1, "a"), Task(2, "b")];
+/// let sorted = ironsea_index::sorted_by_key(tasks);
+/// assert_eq!(sorted.iter().map(|t| t.1).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+/// ```
+pub fn sorted_by_key<R, K, I>(records: I) -> Vec<R>
+where
+    R: Record<K>,
+    K: Ord,
+    I: IntoIterator<Item = R>,
+{
+    let mut records = records.into_iter().collect::<Vec<_>>();
+    sort_by_key(&mut records);
+    records
+}
+
+/// Record paired with a key computed by a function, for indexing records
+/// which do not implement [`Record`] for that key.
+///
+/// This is useful for ad hoc keys, or for foreign types on which
+/// `Record` cannot be implemented. The key is computed once, when the
+/// record is wrapped.
+///
+///  * `R`: Type of the record
+///  * `K`: Type of the key
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyFn<R, K> {
+    key: K,
+    record: R,
+}
+
+impl<R, K> KeyFn<R, K> {
+    /// Wrap `record`, with the key returned by `key_fn`.
+    pub fn new<F>(record: R, key_fn: F) -> Self
+    where
+        F: FnOnce(&R) -> K,
+    {
+        KeyFn {
+            key: key_fn(&record),
+            record,
+        }
+    }
+
+    /// Retrieve a reference to the wrapped record.
+    pub fn get_ref(&self) -> &R {
+        &self.record
+    }
+
+    /// Retrieve the wrapped record.
+    pub fn into_inner(self) -> R {
+        self.record
+    }
+}
+
+impl<R, K: Clone> Record<K> for KeyFn<R, K> {
+    fn key(&self) -> K {
+        self.key.clone()
+    }
+}
+
+/// Record ordered by its key, for storing records in collections relying
+/// on `Ord`, such as `BinaryHeap` or `BTreeSet`.
+///
+/// Comparisons only consider the keys returned by [`Record::key`], which
+/// is called on both sides for each of them. Two records with equal keys
+/// therefore compare equal, even when they differ otherwise, so a
+/// `BTreeSet` keeps only one of them.
+///
+///  * `R`: Type of the record
+///  * `K`: Type of the key
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Reverse;
+/// use std::collections::BinaryHeap;
+///
+/// use ironsea_index::ByKey;
+/// use ironsea_index::Record;
+///
+/// struct Job {
+///     priority: i64,
+///     name: &'static str,
+/// }
+///
+/// impl Record<i64> for Job {
+///     fn key(&self) -> i64 {
+///         self.priority
+///     }
+/// }
+///
+/// let mut heap = BinaryHeap::<ByKey<Job, i64>>::new();
+/// heap.push(ByKey::new(Job { priority: 2, name: "b" }));
+/// heap.push(ByKey::new(Job { priority: 7, name: "c" }));
+/// heap.push(ByKey::new(Job { priority: -1, name: "a" }));
+///
+/// let order = std::iter::from_fn(|| heap.pop())
+///     .map(|job| job.into_inner().name)
+///     .collect::<Vec<_>>();
+/// assert_eq!(order, vec!["c", "b", "a"]);
+///
+/// // Lowest key first.
+/// let mut heap = BinaryHeap::new();
+/// heap.push(Reverse(ByKey::new(Job { priority: 2, name: "b" })));
+/// heap.push(Reverse(ByKey::new(Job { priority: -1, name: "a" })));
+/// assert_eq!(heap.pop().unwrap().0.get_ref().name, "a");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ByKey<R, K> {
+    record: R,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<R, K> ByKey<R, K> {
+    /// Wrap `record`, to order it by its key.
+    pub fn new(record: R) -> Self {
+        ByKey {
+            record,
+            key: PhantomData,
+        }
+    }
+
+    /// Retrieve a reference to the wrapped record.
+    pub fn get_ref(&self) -> &R {
+        &self.record
+    }
+
+    /// Retrieve the wrapped record.
+    pub fn into_inner(self) -> R {
+        self.record
+    }
+}
+
+impl<R: Record<K>, K> Record<K> for ByKey<R, K> {
+    fn key(&self) -> K {
+        self.record.key()
+    }
+}
+
+impl<R: Record<K>, K: Ord> PartialEq for ByKey<R, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.record.key() == other.record.key()
+    }
+}
+
+impl<R: Record<K>, K: Ord> Eq for ByKey<R, K> {}
+
+impl<R: Record<K>, K: Ord> PartialOrd for ByKey<R, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<R: Record<K>, K: Ord> Ord for ByKey<R, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.record.key().cmp(&other.record.key())
+    }
+}
+
+/// Build a sorted index over `records`, keyed by `key_fn` instead of a
+/// [`Record`] implementation.
+///
+/// Each record is wrapped in a [`KeyFn`], so the index stays usable with
+/// all the traits of [`SortedVecIndex`](impls::SortedVecIndex), for
+/// example to insert records later on.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Indexed;
+///
+/// struct Event {
+///     timestamp: u64,
+///     name: &'static str,
+/// }
+///
+/// let events = vec![
+///     Event { timestamp: 7300, name: "b" },
+///     Event { timestamp: 100, name: "a" },
+///     Event { timestamp: 7200, name: "c" },
+/// ];
+///
+/// // Index by hour.
+/// let index = ironsea_index::build_index_with(events, |e| e.timestamp / 3600);
+///
+/// let names = index.find(&2).map(|e| e.get_ref().name).collect::<Vec<_>>();
+/// assert_eq!(names, vec!["b", "c"]);
+/// ```
+pub fn build_index_with<R, K, F>(
+    records: Vec<R>,
+    key_fn: F,
+) -> impls::SortedVecIndex<KeyFn<R, K>, K>
+where
+    K: Ord + Clone,
+    F: Fn(&R) -> K,
+{
+    impls::SortedVecIndex::new(
+        records
+            .into_iter()
+            .map(|record| KeyFn::new(record, &key_fn))
+            .collect(),
+    )
+}
+
+/// Build a sorted index over the records with a key, skipping those
+/// whose [`Record`] implementation returns `None`.
+///
+/// The records without a key are silently dropped, instead of being
+/// indexed under `None`, so they cannot be retrieved from the index.
+/// As with [`build_index_with`], each record is wrapped in a [`KeyFn`],
+/// holding the key without its `Option`.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+/// use ironsea_index::SizedIndex;
+///
+/// struct Contact {
+///     name: &'static str,
+///     phone: Option<u32>,
+/// }
+///
+/// impl Record<Option<u32>> for Contact {
+///     fn key(&self) -> Option<u32> {
+///         self.phone
+///     }
+/// }
+///
+/// let contacts = vec![
+///     Contact { name: "ann", phone: Some(555) },
+///     Contact { name: "bob", phone: None },
+///     Contact { name: "cid", phone: Some(123) },
+///     Contact { name: "dee", phone: None },
+/// ];
+///
+/// let index = ironsea_index::build_skip_none(contacts);
+///
+/// assert_eq!(index.len(), 2);
+/// let names = index
+///     .find_range_bounds(..)
+///     .map(|c| c.get_ref().name)
+///     .collect::<Vec<_>>();
+/// assert_eq!(names, vec!["cid", "ann"]);
+/// assert_eq!(index.find(&555).next().map(|c| c.get_ref().name), Some("ann"));
+/// ```
+pub fn build_skip_none<R, K>(records: Vec<R>) -> impls::SortedVecIndex<KeyFn<R, K>, K>
+where
+    R: Record<Option<K>>,
+    K: Ord + Clone,
+{
+    impls::SortedVecIndex::new(
+        records
+            .into_iter()
+            .filter_map(|record| {
+                let key = record.key()?;
+                Some(KeyFn::new(record, |_| key))
+            })
+            .collect(),
+    )
+}
+
+/// Build a sorted index over `records`, with the records sharing a key
+/// combined into a single one by `combine`.
+///
+/// The records of a key are folded in their original order, as in
+/// `combine(combine(a, b), c)`. `combine` should be associative, so that
+/// the result does not depend on how records are grouped, and
+/// commutative too if the original order of the records is not
+/// meaningful. A key with a single record keeps it unchanged, without
+/// calling `combine`.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+/// use ironsea_index::SizedIndex;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Sales {
+///     day: u32,
+///     total: u64,
+/// }
+///
+/// impl Record<u32> for Sales {
+///     fn key(&self) -> u32 {
+///         self.day
+///     }
+/// }
+///
+/// let sales = vec![
+///     Sales { day: 2, total: 10 },
+///     Sales { day: 1, total: 5 },
+///     Sales { day: 2, total: 7 },
+///     Sales { day: 3, total: 1 },
+///     Sales { day: 2, total: 3 },
+/// ];
+///
+/// let index = ironsea_index::build_aggregated(sales, |a, b| Sales {
+///     day: a.day,
+///     total: a.total + b.total,
+/// });
+///
+/// assert_eq!(index.len(), 3);
+/// assert_eq!(index.find(&2).collect::<Vec<_>>(), vec![&Sales { day: 2, total: 20 }]);
+/// assert_eq!(index.find(&3).collect::<Vec<_>>(), vec![&Sales { day: 3, total: 1 }]);
+/// ```
+pub fn build_aggregated<R, K, A>(records: Vec<R>, combine: A) -> impls::SortedVecIndex<R, K>
+where
+    R: Record<K>,
+    K: Ord,
+    A: Fn(R, R) -> R,
+{
+    let mut aggregated = Vec::<R>::new();
+
+    for record in sorted_by_key(records) {
+        let record = match aggregated.last() {
+            Some(last) if last.key() == record.key() => {
+                let last = aggregated.pop().unwrap();
+                combine(last, record)
+            }
+            _ => record,
+        };
+        aggregated.push(record);
+    }
+
+    impls::SortedVecIndex::new(aggregated)
+}
+
+/// Remove the records sharing their [`Record::key`] with a later record,
+/// keeping the last occurrence of each key.
+///
+/// The remaining records keep their relative order. The keys are
+/// tracked in a hash set, so this is in O(n) but requires `K: Hash`,
+/// and the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct MyPair {
+///     a: i64,
+///     b: i64,
+/// }
+///
+/// impl Record<i64> for MyPair {
+///     fn key(&self) -> i64 {
+///         self.a
+///     }
+/// }
+///
+/// let records = vec![
+///     MyPair { a: 1, b: 10 },
+///     MyPair { a: 2, b: 20 },
+///     MyPair { a: 1, b: 11 },
+///     MyPair { a: 3, b: 30 },
+///     MyPair { a: 2, b: 21 },
+/// ];
+///
+/// assert_eq!(
+///     ironsea_index::dedup_by_key(records),
+///     vec![
+///         MyPair { a: 1, b: 11 },
+///         MyPair { a: 3, b: 30 },
+///         MyPair { a: 2, b: 21 },
+///     ]
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn dedup_by_key<R, K>(records: Vec<R>) -> Vec<R>
+where
+    R: Record<K>,
+    K: Eq + Hash,
+{
+    let mut seen = HashSet::with_capacity(records.len());
+
+    let mut records = records
+        .into_iter()
+        .rev()
+        .filter(|r| seen.insert(r.key()))
+        .collect::<Vec<_>>();
+    records.reverse();
+    records
+}
+
+/// Remove the records sharing their [`Record::key`] with an earlier
+/// record, keeping the first occurrence of each key.
+///
+/// As for [`dedup_by_key`], the remaining records keep their relative
+/// order, and this is in O(n).
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Task(u32, &'static str);
+///
+/// impl Record<u32> for Task {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let tasks = vec![Task(2, "a"), Task(1, "b"), Task(2, "c")];
+/// assert_eq!(
+///     ironsea_index::dedup_keep_first(tasks),
+///     vec![Task(2, "a"), Task(1, "b")]
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn dedup_keep_first<R, K>(mut records: Vec<R>) -> Vec<R>
+where
+    R: Record<K>,
+    K: Eq + Hash,
+{
+    let mut seen = HashSet::with_capacity(records.len());
+
+    records.retain(|r| seen.insert(r.key()));
+    records
+}
+
+/// Reconstruction of records from their destructured parts.
+///
+/// This is the inverse of [`Record`] and [`RecordFields`]: for a
+/// round-trip correct implementation, `build(r.key(), r.fields())` must
+/// be equal to `r`.
 ///
 ///  * `R`: Type of the records
 ///  * `K`: Type of the keys
+///  * `F`: Type of the struct containing the remaining fields
+pub trait RecordBuilder<R, K, F> {
+    /// Build a record from its key and fields.
+    fn build(key: K, fields: F) -> R;
+}
 
-// Generic types are not sorted alphabetically, to match next trait
-// semantic order
-pub trait Indexed<R, K> {
-    /// Retrieve all records matching the key.
-    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &R> + 'i>;
+/// Record behavior used by indices storing records under multiple keys.
+///
+/// Some records map to several keys of the same type, for example a
+/// document with multiple tags. Indices accepting this trait store the
+/// record under each of the returned keys.
+///
+/// This is implemented for every [`Record`] implementation, returning its
+/// only key, so that single-key records can be used unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::MultiKeyIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::RecordKeys;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Document {
+///     title: String,
+///     tags: Vec<String>,
+/// }
+///
+/// impl RecordKeys<String> for Document {
+///     fn keys(&self) -> Vec<String> {
+///         self.tags.clone()
+///     }
+/// }
+///
+/// let index = MultiKeyIndex::new(vec![
+///     Document {
+///         title: "Iron Sea".to_string(),
+///         tags: vec!["database".to_string(), "rust".to_string()],
+///     },
+///     Document {
+///         title: "Other".to_string(),
+///         tags: vec!["misc".to_string()],
+///     },
+/// ]);
+///
+/// let by_database = index.find(&"database".to_string()).collect::<Vec<_>>();
+/// let by_rust = index.find(&"rust".to_string()).collect::<Vec<_>>();
+///
+/// assert_eq!(by_database.len(), 1);
+/// assert_eq!(by_database, by_rust);
+/// assert_eq!(by_rust[0].title, "Iron Sea");
+/// ```
+pub trait RecordKeys<K> {
+    /// Extract all the keys of the record.
+    fn keys(&self) -> Vec<K>;
+}
 
-    /// Retrieve all records matching in the key range defined by
-    /// `start` and `end`.
-    ///
-    /// * `start` is included
-    // TODO: TBC for `end`
-    fn find_range<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &R> + 'i>;
+impl<T, K> RecordKeys<K> for T
+where
+    T: Record<K>,
+{
+    fn keys(&self) -> Vec<K> {
+        vec![self.key()]
+    }
 }
 
-/// Methods provided by destructuring indices.
+/// Record behavior used by indices of records spanning a range of keys.
 ///
-/// This kind of indices store inside the index both keys and values,
-/// meaning the original records can be freed.
+/// The interval `(lo, hi)` includes both of its bounds, and is expected
+/// to have `lo <= hi`. Records whose interval is reversed are considered
+/// empty: they never overlap any query.
 ///
-///  * `F`: Type of the struct containing the remaining fields
-///  * `K`: Type of the keys
-pub trait IndexedDestructured<F, K> {
-    /// Retrieve all records matching the key.
-    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &F> + 'i>;
+/// See [`IntervalIndex`] for an example.
+pub trait RecordInterval<K> {
+    /// Extract the interval of keys spanned by the record, as its
+    /// `(lo, hi)` bounds, both included.
+    fn interval(&self) -> (K, K);
+}
 
-    /// Retrieve all records matching in the key range defined by
-    /// `start` and `end`.
-    ///
-    /// * `start` is included
-    // TODO: TBC for `end`
-    fn find_range<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = (K, &F)> + 'i>;
+/// Composite keys built from multiple [`Record`] implementations.
+///
+/// For any type implementing both `Record<A>` and `Record<B>`, this
+/// provides the pair of keys `(A, B)`, which can be used to sort or
+/// index on both keys at once.
+///
+/// Tuples are ordered lexicographically, so records are ordered by their
+/// `A` key first, and only records sharing the same `A` key are ordered
+/// by their `B` key.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::CompositeKey;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug)]
+/// struct Event {
+///     region: String,
+///     timestamp: u64,
+/// }
+///
+/// impl Record<String> for Event {
+///     fn key(&self) -> String {
+///         self.region.clone()
+///     }
+/// }
+///
+/// impl Record<u64> for Event {
+///     fn key(&self) -> u64 {
+///         self.timestamp
+///     }
+/// }
+///
+/// let mut events = vec![
+///     Event { region: "west".to_string(), timestamp: 2 },
+///     Event { region: "east".to_string(), timestamp: 3 },
+///     Event { region: "west".to_string(), timestamp: 1 },
+///     Event { region: "east".to_string(), timestamp: 1 },
+/// ];
+///
+/// events.sort_by_key(|e| e.composite_key::<String, u64>());
+///
+/// let sorted = events
+///     .iter()
+///     .map(|e| (e.region.as_str(), e.timestamp))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(sorted, vec![("east", 1), ("east", 3), ("west", 1), ("west", 2)]);
+/// ```
+pub trait CompositeKey {
+    /// Extract both the `A` and `B` keys from the record.
+    fn composite_key<A, B>(&self) -> (A, B)
+    where
+        Self: Record<A> + Record<B>,
+    {
+        (Record::<A>::key(self), Record::<B>::key(self))
+    }
+}
+
+impl<T: ?Sized> CompositeKey for T {}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Marker of the key types known to this crate.
+///
+/// This is a central place to document what makes a good key type:
+///
+///  * Keys must be totally ordered, consistently with `Eq`, as sorted
+///    indices rely on `Ord` to find them.
+///  * A record type implements [`Record<K>`](Record) once per key type,
+///    so two keys of a record must have different types. Wrapping one of
+///    them in a newtype is the usual way around this.
+///  * Keys are returned by value by [`Record::key`], so they should be
+///    cheap to produce, as integers are.
+///
+/// This trait is sealed: it is implemented for the integer types, `bool`,
+/// `char`, `String` and tuples of up to three key types, and cannot be
+/// implemented outside of this crate.
+///
+/// The index builders, such as
+/// [`SortedVecIndex::new`](impls::SortedVecIndex::new) or
+/// [`build_index_with`], deliberately do not require it. As the trait is
+/// sealed, such a bound would reject every key type defined outside of
+/// this crate, including the newtypes recommended above, as well as
+/// borrowed keys such as `&str`. Using an unsuitable key type with an
+/// index is therefore reported by the bounds the index actually needs,
+/// usually `Ord`, not by this trait. It is meant as a bound for generic
+/// code restricting itself to these types, which then reports other key
+/// types as not implementing `KeyType`.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::KeyType;
+///
+/// fn describe<K: KeyType + std::fmt::Debug>(key: K) -> String {
+///     format!("{:?}", key)
+/// }
+///
+/// assert_eq!(describe((1u8, 'a')), "(1, 'a')");
+/// ```
+///
+/// Other key types are rejected by such code:
+///
+/// ```compile_fail
+/// use ironsea_index::KeyType;
+///
+/// fn describe<K: KeyType + std::fmt::Debug>(key: K) -> String {
+///     format!("{:?}", key)
+/// }
+///
+/// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Id(u32);
+///
+/// // error: the trait bound `Id: KeyType` is not satisfied
+/// describe(Id(7));
+/// ```
+///
+/// And it cannot be implemented for them:
+///
+/// ```compile_fail
+/// use ironsea_index::KeyType;
+///
+/// #[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// struct Id(u32);
+///
+/// impl KeyType for Id {}
+/// ```
+pub trait KeyType: Ord + sealed::Sealed {}
+
+macro_rules! impl_key_type {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+            impl KeyType for $t {}
+        )*
+    };
+}
+
+impl_key_type!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, bool, char, String);
+
+impl<A: KeyType, B: KeyType> sealed::Sealed for (A, B) {}
+impl<A: KeyType, B: KeyType> KeyType for (A, B) {}
+impl<A: KeyType, B: KeyType, C: KeyType> sealed::Sealed for (A, B, C) {}
+impl<A: KeyType, B: KeyType, C: KeyType> KeyType for (A, B, C) {}
+
+/// Record behavior used by IndexedDestructured implementations.
+///
+/// RecordFields is used by indices which de-structure records into two
+/// components, the key and the fields associated to that unique key.
+/// This is provided by the users of indices, for each of their `struct`
+/// they wish to index.
+pub trait RecordFields<F> {
+    /// Extract the fields of the record
+    fn fields(&self) -> F;
+}
+
+/// Records behind a reference have the fields of the record they point
+/// to.
+impl<F, R> RecordFields<F> for &R
+where
+    R: RecordFields<F> + ?Sized,
+{
+    fn fields(&self) -> F {
+        (**self).fields()
+    }
+}
+
+/// Boxed records have the fields of the record they contain.
+impl<F, R> RecordFields<F> for Box<R>
+where
+    R: RecordFields<F> + ?Sized,
+{
+    fn fields(&self) -> F {
+        (**self).fields()
+    }
+}
+
+/// Methods provided by indices.
+///
+/// This kind of indices can work on references to the original vector
+/// or take ownership of the records, based on the type given for the
+/// records.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+// Generic types are not sorted alphabetically, to match next trait
+// semantic order
+pub trait Indexed<R, K> {
+    /// Retrieve all records matching the key.
+    ///
+    /// The records are produced lazily, so callers only interested in
+    /// the first few matches do not pay for the rest.
+    ///
+    /// When no record matches, implementations should return
+    /// `Box::new(iter::empty())`: as `iter::Empty` is zero-sized, boxing
+    /// it does not allocate. The implementations provided by this crate
+    /// do so.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use std::ops::RangeBounds;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// struct Index(Vec<(i64, u32)>);
+    ///
+    /// impl Indexed<(i64, u32), i64> for Index {
+    ///     fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    ///         let key = *key;
+    ///         Box::new(self.0.iter().filter(move |r| r.0 == key))
+    ///     }
+    /// #
+    /// #    fn find_bounds<'i>(
+    /// #        &'i self,
+    /// #        start: Bound<&i64>,
+    /// #        end: Bound<&i64>,
+    /// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let range = (start.cloned(), end.cloned());
+    /// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+    /// #    }
+    /// }
+    ///
+    /// let index = Index((0..10).map(|v| (v % 2, v as u32)).collect());
+    ///
+    /// let first: Vec<_> = index.find(&1).take(3).collect();
+    /// assert_eq!(first, vec![&(1, 1), &(1, 3), &(1, 5)]);
+    /// ```
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i R> + 'i>;
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`.
+    ///
+    /// * `start` is included
+    /// * `end` is included
+    ///
+    /// This is an alias of
+    /// [`find_range_inclusive`](Indexed::find_range_inclusive), and
+    /// therefore equivalent to calling
+    /// [`find_range_bounds`](Indexed::find_range_bounds) with
+    /// `start..=end`. As for [`find`](Indexed::find), the records are
+    /// produced lazily.
+    ///
+    /// The records are returned in key order, records sharing a key
+    /// being returned in the same order as by [`find`](Indexed::find).
+    /// Callers which do not need this order can use
+    /// [`find_range_unordered`](Indexed::find_range_unordered).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::ops::Bound;
+    /// # use std::ops::RangeBounds;
+    /// #
+    /// # use ironsea_index::Indexed;
+    /// #
+    /// # struct Index(Vec<(i64, u32)>);
+    /// #
+    /// # impl Indexed<(i64, u32), i64> for Index {
+    /// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let key = *key;
+    /// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+    /// #    }
+    /// #
+    /// #    fn find_bounds<'i>(
+    /// #        &'i self,
+    /// #        start: Bound<&i64>,
+    /// #        end: Bound<&i64>,
+    /// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let range = (start.cloned(), end.cloned());
+    /// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+    /// #    }
+    /// # }
+    /// let index = Index((0..10).map(|v| (v, v as u32)).collect());
+    ///
+    /// let first: Vec<_> = index.find_range(&2, &8).take(3).collect();
+    /// assert_eq!(first, vec![&(2, 2), &(3, 3), &(4, 4)]);
+    ///
+    /// assert_eq!(index.find_range(&2, &8).last(), Some(&(8, 8)));
+    /// ```
+    fn find_range<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.find_range_inclusive(start, end)
+    }
+
+    /// Retrieve all records whose key is within the range defined by
+    /// the `start` and `end` bounds.
+    ///
+    /// Each bound can be included, excluded or unbounded. This is the
+    /// range primitive of the trait, on which all the other range
+    /// queries are built; callers will usually prefer
+    /// [`find_range_bounds`](Indexed::find_range_bounds), which accepts
+    /// the range syntax.
+    ///
+    /// Implementations must return:
+    ///  * no records when the range is empty, for example when its
+    ///    start is greater than its end,
+    ///  * every record of the index for a fully unbounded range,
+    ///  * the records in key order, records sharing a key being in the
+    ///    same order as returned by [`find`](Indexed::find).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use std::ops::RangeBounds;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// struct Index(Vec<(i64, u32)>);
+    ///
+    /// impl Indexed<(i64, u32), i64> for Index {
+    /// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let key = *key;
+    /// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+    /// #    }
+    /// #
+    ///     fn find_bounds<'i>(
+    ///         &'i self,
+    ///         start: Bound<&i64>,
+    ///         end: Bound<&i64>,
+    ///     ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    ///         let range = (start.cloned(), end.cloned());
+    ///         Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+    ///     }
+    /// }
+    ///
+    /// let index = Index((0..10).map(|v| (v, v as u32)).collect());
+    ///
+    /// assert_eq!(index.find_bounds(Bound::Excluded(&2), Bound::Included(&5)).count(), 3);
+    /// assert_eq!(index.find_bounds(Bound::Unbounded, Bound::Excluded(&4)).count(), 4);
+    ///
+    /// // The trait can be used as a trait object.
+    /// let index: &dyn Indexed<(i64, u32), i64> = &index;
+    /// assert_eq!(index.find_range(&2, &4).count(), 3);
+    /// assert_eq!(index.find_from(&7).count(), 3);
+    /// ```
+    fn find_bounds<'i>(
+        &'i self,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i>;
+
+    /// Retrieve all records whose key is contained in `range`.
+    ///
+    /// Each end of the range can be included, excluded or unbounded,
+    /// so `start..end`, `start..=end`, `start..` or `..` are all valid
+    /// queries. This is a shorthand for
+    /// [`find_bounds`](Indexed::find_bounds), with the same guarantees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// assert_eq!(index.find_range_bounds(2..5).count(), 3);
+    /// assert_eq!(index.find_range_bounds(2..=5).count(), 4);
+    /// assert_eq!(index.find_range_bounds(7..).count(), 3);
+    /// assert_eq!(index.find_range_bounds(..).count(), 10);
+    ///
+    /// // Empty ranges
+    /// assert_eq!(index.find_range_bounds(5..5).count(), 0);
+    /// assert_eq!(index.find_range(&5, &2).count(), 0);
+    /// ```
+    fn find_range_bounds<'i, B: RangeBounds<K>>(
+        &'i self,
+        range: B,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i>
+    where
+        Self: Sized,
+    {
+        self.find_bounds(range.start_bound(), range.end_bound())
+    }
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// assert_eq!(index.find_range_inclusive(&2, &4).count(), 3);
+    /// assert_eq!(index.find_range_inclusive(&3, &3).collect::<Vec<_>>(), vec![&3]);
+    /// ```
+    fn find_range_inclusive<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.find_bounds(Bound::Included(start), Bound::Included(end))
+    }
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start`, included, and `end`, excluded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// assert_eq!(index.find_range_exclusive_end(&2, &4).count(), 2);
+    /// assert_eq!(index.find_range_exclusive_end(&3, &3).count(), 0);
+    /// ```
+    fn find_range_exclusive_end<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.find_bounds(Bound::Included(start), Bound::Excluded(end))
+    }
+
+    /// Retrieve all records whose key is greater than or equal to
+    /// `start`, in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// assert_eq!(index.find_from(&7).collect::<Vec<_>>(), vec![&7, &8, &9]);
+    /// assert_eq!(index.find_from(&-5).count(), 10);
+    /// assert_eq!(index.find_from(&10).count(), 0);
+    /// ```
+    fn find_from<'i>(&'i self, start: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.find_bounds(Bound::Included(start), Bound::Unbounded)
+    }
+
+    /// Retrieve all records whose key is less than or equal to `end`,
+    /// in key order.
+    ///
+    /// As for [`find_range`](Indexed::find_range), `end` is included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// assert_eq!(index.find_until(&2).collect::<Vec<_>>(), vec![&0, &1, &2]);
+    /// assert_eq!(index.find_until(&20).count(), 10);
+    /// assert_eq!(index.find_until(&-1).count(), 0);
+    /// ```
+    fn find_until<'i>(&'i self, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        self.find_bounds(Bound::Unbounded, Bound::Included(end))
+    }
+
+    /// Count the records matching the key.
+    ///
+    /// The default implementation walks through the result of
+    /// [`find`](Indexed::find), and is therefore in O(matches).
+    /// Implementations which can answer directly from their internal
+    /// structure, for example with two binary searches on a sorted
+    /// array, are encouraged to override it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::ops::Bound;
+    /// # use std::ops::RangeBounds;
+    /// #
+    /// # use ironsea_index::Indexed;
+    /// #
+    /// # struct Index(Vec<(i64, u32)>);
+    /// #
+    /// # impl Indexed<(i64, u32), i64> for Index {
+    /// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let key = *key;
+    /// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+    /// #    }
+    /// #
+    /// #    fn find_bounds<'i>(
+    /// #        &'i self,
+    /// #        start: Bound<&i64>,
+    /// #        end: Bound<&i64>,
+    /// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let range = (start.cloned(), end.cloned());
+    /// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+    /// #    }
+    /// # }
+    /// let index = Index((0..10).map(|v| (v % 3, v as u32)).collect());
+    ///
+    /// assert_eq!(index.count(&0), 4);
+    /// assert_eq!(index.count(&3), 0);
+    /// ```
+    fn count(&self, key: &K) -> usize {
+        self.find(key).count()
+    }
+
+    /// Count the records matching in the key range defined by `start`
+    /// and `end`, both included.
+    ///
+    /// As for [`count`](Indexed::count), the default implementation is
+    /// in O(matches) and implementations are encouraged to override it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::ops::Bound;
+    /// # use std::ops::RangeBounds;
+    /// #
+    /// # use ironsea_index::Indexed;
+    /// #
+    /// # struct Index(Vec<(i64, u32)>);
+    /// #
+    /// # impl Indexed<(i64, u32), i64> for Index {
+    /// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let key = *key;
+    /// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+    /// #    }
+    /// #
+    /// #    fn find_bounds<'i>(
+    /// #        &'i self,
+    /// #        start: Bound<&i64>,
+    /// #        end: Bound<&i64>,
+    /// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let range = (start.cloned(), end.cloned());
+    /// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+    /// #    }
+    /// # }
+    /// let index = Index((0..10).map(|v| (v % 3, v as u32)).collect());
+    ///
+    /// assert_eq!(index.count_range(&1, &2), 6);
+    /// assert_eq!(index.count_range(&2, &1), 0);
+    /// ```
+    fn count_range(&self, start: &K, end: &K) -> usize {
+        self.find_range(start, end).count()
+    }
+
+    /// Check whether at least one record matches the key.
+    ///
+    /// The default implementation stops at the first record returned by
+    /// [`find`](Indexed::find). Implementations over sorted structures
+    /// can override it with a single binary search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::ops::Bound;
+    /// # use std::ops::RangeBounds;
+    /// #
+    /// # use ironsea_index::Indexed;
+    /// #
+    /// # struct Index(Vec<(i64, u32)>);
+    /// #
+    /// # impl Indexed<(i64, u32), i64> for Index {
+    /// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let key = *key;
+    /// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+    /// #    }
+    /// #
+    /// #    fn find_bounds<'i>(
+    /// #        &'i self,
+    /// #        start: Bound<&i64>,
+    /// #        end: Bound<&i64>,
+    /// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+    /// #        let range = (start.cloned(), end.cloned());
+    /// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+    /// #    }
+    /// # }
+    /// let empty = Index(vec![]);
+    /// assert!(!empty.contains_key(&1));
+    ///
+    /// let index = Index(vec![(1, 10), (1, 11), (2, 20)]);
+    /// assert!(index.contains_key(&1));
+    /// assert!(!index.contains_key(&3));
+    /// ```
+    fn contains_key(&self, key: &K) -> bool {
+        self.find(key).next().is_some()
+    }
+
+    /// Call `f` on all records matching in the key range defined by
+    /// `start` and `end`, both included, in key order.
+    ///
+    /// Iteration stops as soon as `f` returns `ControlFlow::Break`, whose
+    /// value is then returned.
+    ///
+    /// The default implementation iterates over the result of
+    /// [`find_range`](Indexed::find_range), implementations are free to
+    /// override it to stream directly from their internal structure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use std::ops::ControlFlow;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k * 10])).collect::<BTreeMap<_, _>>();
+    ///
+    /// let mut sum = 0;
+    /// let _ = index.for_each_in_range(&2, &4, |v| {
+    ///     sum += v;
+    ///     ControlFlow::<()>::Continue(())
+    /// });
+    /// assert_eq!(sum, 90);
+    ///
+    /// // Stop at the first value above 50.
+    /// let found = index.for_each_in_range(&0, &9, |v| {
+    ///     if *v > 50 {
+    ///         ControlFlow::Break(*v)
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    /// assert_eq!(found, ControlFlow::Break(60));
+    /// ```
+    fn for_each_in_range<B, F>(&self, start: &K, end: &K, f: F) -> ControlFlow<B>
+    where
+        Self: Sized,
+        F: FnMut(&R) -> ControlFlow<B>,
+    {
+        self.find_range(start, end).try_for_each(f)
+    }
+
+    /// Call `f` on all records matching in the key range defined by
+    /// `start` and `end`, both included, in key order, stopping at the
+    /// first error returned by `f`.
+    ///
+    /// This is meant to stream a range into a fallible sink, such as a
+    /// writer. On error, the only guarantee is that the records visited
+    /// so far were passed to `f`; the remaining ones are not visited.
+    ///
+    /// The default implementation iterates over the result of
+    /// [`find_range`](Indexed::find_range).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// let mut out = vec![];
+    /// let result = index.try_for_each_in_range(&2, &8, |v| {
+    ///     if out.len() == 3 {
+    ///         return Err("full");
+    ///     }
+    ///     out.push(*v);
+    ///     Ok(())
+    /// });
+    ///
+    /// assert_eq!(result, Err("full"));
+    /// assert_eq!(out, vec![2, 3, 4]);
+    ///
+    /// let mut visited = 0;
+    /// let result = index.try_for_each_in_range(&2, &8, |_| {
+    ///     visited += 1;
+    ///     Ok::<_, ()>(())
+    /// });
+    /// assert_eq!((result, visited), (Ok(()), 7));
+    /// ```
+    fn try_for_each_in_range<E, F>(&self, start: &K, end: &K, f: F) -> Result<(), E>
+    where
+        Self: Sized,
+        F: FnMut(&R) -> Result<(), E>,
+    {
+        self.find_range(start, end).try_for_each(f)
+    }
+
+    /// Retrieve a page of the records matching in the key range defined
+    /// by `start` and `end`, both included, skipping the first `offset`
+    /// records and returning at most `limit` records.
+    ///
+    /// Pages are only stable if [`find_range`](Indexed::find_range)
+    /// returns records in a deterministic order: by key, then in
+    /// insertion order for records sharing a key. Consecutive pages then
+    /// neither overlap nor skip records.
+    ///
+    /// The default implementation skips over the result of `find_range`,
+    /// implementations are free to override it to seek directly to the
+    /// requested page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::Record;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct MyPair {
+    ///     a: i64,
+    ///     b: i64,
+    /// }
+    ///
+    /// impl Record<i64> for MyPair {
+    ///     fn key(&self) -> i64 {
+    ///         self.a
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new((0..7).map(|b| MyPair { a: b / 2, b }).collect());
+    ///
+    /// let mut pages = vec![];
+    /// let mut offset = 0;
+    /// loop {
+    ///     let page = index
+    ///         .find_range_paged(&0, &2, offset, 2)
+    ///         .map(|r| r.b)
+    ///         .collect::<Vec<_>>();
+    ///     if page.is_empty() {
+    ///         break;
+    ///     }
+    ///     offset += page.len();
+    ///     pages.push(page);
+    /// }
+    ///
+    /// assert_eq!(pages, vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+    /// ```
+    fn find_range_paged<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+        offset: usize,
+        limit: usize,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        Box::new(self.find_range(start, end).skip(offset).take(limit))
+    }
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, in successive chunks of
+    /// `chunk_size` records.
+    ///
+    /// The chunks are produced lazily, in key order, so that only one
+    /// of them is held in memory at a time. All the chunks hold
+    /// `chunk_size` records, except the last which can be shorter. An
+    /// empty range produces no chunk.
+    ///
+    /// # Panics
+    ///
+    /// When `chunk_size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// let chunks = index.find_range_chunks(&3, &7, 2).collect::<Vec<_>>();
+    /// assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+    /// assert_eq!(chunks.concat(), index.find_range(&3, &7).collect::<Vec<_>>());
+    ///
+    /// assert_eq!(index.find_range_chunks(&7, &3, 2).count(), 0);
+    /// ```
+    fn find_range_chunks<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+        chunk_size: usize,
+    ) -> Box<dyn Iterator<Item = Vec<&'i R>> + 'i> {
+        assert!(chunk_size > 0, "chunk_size must not be 0");
+
+        let mut records = self.find_range(start, end);
+        Box::new(iter::from_fn(move || {
+            let chunk = records.by_ref().take(chunk_size).collect::<Vec<_>>();
+
+            if chunk.is_empty() {
+                None
+            } else {
+                Some(chunk)
+            }
+        }))
+    }
+
+    /// Retrieve, in key order, the first `limit` records matching in the
+    /// key range defined by `start` and `end`, both included, for which
+    /// `predicate` returns `true`.
+    ///
+    /// The scan stops as soon as `limit` records are found, so the
+    /// predicate is not evaluated on the rest of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..1000).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// let calls = Cell::new(0);
+    /// let found = index.find_range_where(
+    ///     &0,
+    ///     &999,
+    ///     |v| {
+    ///         calls.set(calls.get() + 1);
+    ///         v % 3 == 0
+    ///     },
+    ///     2,
+    /// );
+    ///
+    /// assert_eq!(found, vec![&0, &3]);
+    /// assert_eq!(calls.get(), 4);
+    /// ```
+    fn find_range_where<'i, P>(
+        &'i self,
+        start: &K,
+        end: &K,
+        predicate: P,
+        limit: usize,
+    ) -> Vec<&'i R>
+    where
+        Self: Sized,
+        P: Fn(&R) -> bool,
+    {
+        self.find_range(start, end)
+            .filter(|r| predicate(r))
+            .take(limit)
+            .collect()
+    }
+
+    /// Retrieve all records matching in the key range defined by `start`
+    /// and `end`, both included, for which `predicate` returns `true`.
+    ///
+    /// The predicate is evaluated by multiple threads, each filtering a
+    /// contiguous chunk of the range, and the results are returned in
+    /// the same order as [`find_range`](Indexed::find_range).
+    ///
+    /// The records of the range are first collected into a `Vec`, then
+    /// each call spawns up to `available_parallelism()` scoped threads,
+    /// each given at least 1024 records. Smaller ranges are therefore
+    /// filtered on the current thread, as spawning would cost more than
+    /// it saves. This pays off for large ranges with costly predicates.
+    ///
+    /// This requires the `parallel` feature, which uses the threads of
+    /// the standard library, not `rayon`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..100_000).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// let parallel = index.par_find_range(&100, &90_000, |v| v % 7 == 0);
+    /// let sequential = index
+    ///     .find_range(&100, &90_000)
+    ///     .filter(|v| *v % 7 == 0)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(parallel, sequential);
+    ///
+    /// // Small ranges stay on the current thread.
+    /// assert_eq!(index.par_find_range(&0, &9, |v| v % 2 == 0), vec![&0, &2, &4, &6, &8]);
+    /// ```
+    #[cfg(feature = "parallel")]
+    fn par_find_range<'i, P>(&'i self, start: &K, end: &K, predicate: P) -> Vec<&'i R>
+    where
+        Self: Sized,
+        R: Sync,
+        K: Sync,
+        P: Fn(&R) -> bool + Sync,
+    {
+        // Fewest records given to a thread.
+        const MIN_CHUNK: usize = 1024;
+
+        let records = self.find_range(start, end).collect::<Vec<_>>();
+
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(records.len() / MIN_CHUNK);
+        if threads <= 1 {
+            return records.into_iter().filter(|r| predicate(r)).collect();
+        }
+        let chunk = records.len().div_ceil(threads);
+
+        std::thread::scope(|scope| {
+            let predicate = &predicate;
+            let handles = records
+                .chunks(chunk)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .copied()
+                            .filter(|r| predicate(r))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// Retrieve the records matching each of the given keys.
+    ///
+    /// The results are returned in the order of `keys`, each key being
+    /// paired with its records, including when a key is requested
+    /// multiple times.
+    ///
+    /// The default implementation calls [`find`](Indexed::find) for each
+    /// key. Implementations over sorted structures are encouraged to
+    /// override it to sweep their structure only once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let mut index = BTreeMap::new();
+    /// index.insert(1, vec!["a", "b"]);
+    /// index.insert(2, vec!["c"]);
+    ///
+    /// assert_eq!(
+    ///     index.find_many(&[2, 3, 1, 2]),
+    ///     vec![
+    ///         (&2, vec![&"c"]),
+    ///         (&3, vec![]),
+    ///         (&1, vec![&"a", &"b"]),
+    ///         (&2, vec![&"c"]),
+    ///     ]
+    /// );
+    /// ```
+    fn find_many<'i>(&'i self, keys: &'i [K]) -> Vec<(&'i K, Vec<&'i R>)> {
+        keys.iter()
+            .map(|key| (key, self.find(key).collect()))
+            .collect()
+    }
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, in descending key order.
+    ///
+    /// This is exactly the reverse of [`find_range`](Indexed::find_range),
+    /// so records sharing a key are returned in reverse insertion order as
+    /// well.
+    ///
+    /// The default implementation collects the result of `find_range`
+    /// before reversing it. Sorted implementations are encouraged to
+    /// override it to walk their structure backwards instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Event {
+    ///     time: u64,
+    ///     id: u32,
+    /// }
+    ///
+    /// impl Record<u64> for Event {
+    ///     fn key(&self) -> u64 {
+    ///         self.time
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new(vec![
+    ///     Event { time: 1, id: 0 },
+    ///     Event { time: 5, id: 1 },
+    ///     Event { time: 3, id: 2 },
+    ///     Event { time: 5, id: 3 },
+    /// ]);
+    ///
+    /// // The three latest events, most recent first.
+    /// let latest = index
+    ///     .find_range_rev(&0, &10)
+    ///     .take(3)
+    ///     .map(|e| e.id)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(latest, vec![3, 1, 2]);
+    /// ```
+    fn find_range_rev<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = &'i R> + 'i> {
+        Box::new(
+            self.find_range(start, end)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev(),
+        )
+    }
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, unless there are more than
+    /// `max` of them.
+    ///
+    /// This protects services exposing range queries from adversarial
+    /// requests spanning most of the index, which could otherwise
+    /// exhaust their memory.
+    ///
+    /// The default implementation checks the size of the range with
+    /// [`count_range`](Indexed::count_range) before retrieving it, so it
+    /// is cheap when `count_range` is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::RangeTooLarge;
+    ///
+    /// let index = (0..100).map(|k| (k, vec![k])).collect::<BTreeMap<_, _>>();
+    ///
+    /// assert_eq!(index.find_range_capped(&10, &12, 5), Ok(vec![&10, &11, &12]));
+    /// assert_eq!(
+    ///     index.find_range_capped(&0, &49, 5),
+    ///     Err(RangeTooLarge { count: 50, max: 5 })
+    /// );
+    /// ```
+    fn find_range_capped<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+        max: usize,
+    ) -> Result<Vec<&'i R>, RangeTooLarge> {
+        let count = self.count_range(start, end);
+        if count > max {
+            return Err(RangeTooLarge { count, max });
+        }
+
+        Ok(self.find_range(start, end).collect())
+    }
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, in no particular order.
+    ///
+    /// This returns the same records as
+    /// [`find_range`](Indexed::find_range), but without its ordering
+    /// guarantee: implementations which do not store their records by
+    /// key, for example hash-partitioned ones, can override it to answer
+    /// without sorting. The default implementation collects
+    /// [`find_range`](Indexed::find_range), and is therefore ordered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ironsea_index::Indexed;
+    ///
+    /// let index = (0..10)
+    ///     .map(|k| (k, vec![(k, 'b'), (k, 'a')]))
+    ///     .collect::<BTreeMap<_, _>>();
+    ///
+    /// let ordered = index.find_range(&2, &7).collect::<Vec<_>>();
+    /// assert!(ordered.windows(2).all(|w| w[0].0 <= w[1].0));
+    ///
+    /// let mut unordered = index.find_range_unordered(&2, &7);
+    /// unordered.sort();
+    /// let mut expected = ordered.clone();
+    /// expected.sort();
+    /// assert_eq!(unordered, expected);
+    /// ```
+    fn find_range_unordered<'i>(&'i self, start: &K, end: &K) -> Vec<&'i R> {
+        self.find_range(start, end).collect()
+    }
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, returning each record at most
+    /// once.
+    ///
+    /// Indices storing records under multiple keys, as with
+    /// [`RecordKeys`], return a record once for each of its keys in the
+    /// range from [`find_range`](Indexed::find_range). Here, records are
+    /// distinct when they are stored at different addresses, so no bound
+    /// is required on `R`, but records which are equal without being the
+    /// same stored record are all returned. The first occurrence of each
+    /// record is kept, in key order.
+    ///
+    /// Records of zero-sized types can share their address, and are then
+    /// returned only once by the default implementation. The indices
+    /// provided by this crate do not rely on addresses, but on the
+    /// positions of the records they store.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::MultiKeyIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::RecordKeys;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Span(u32, u32);
+    ///
+    /// impl RecordKeys<u32> for Span {
+    ///     fn keys(&self) -> Vec<u32> {
+    ///         (self.0..=self.1).collect()
+    ///     }
+    /// }
+    ///
+    /// let index = MultiKeyIndex::new(vec![Span(1, 3), Span(2, 2), Span(2, 2)]);
+    ///
+    /// // `Span(1, 3)` matches under 2 and 3.
+    /// assert_eq!(index.find_range(&2, &3).count(), 4);
+    /// assert_eq!(
+    ///     index.find_range_dedup(&2, &3),
+    ///     vec![&Span(1, 3), &Span(2, 2), &Span(2, 2)]
+    /// );
+    ///
+    /// // Zero-sized records are told apart by their position.
+    /// struct Marker;
+    ///
+    /// impl RecordKeys<u32> for Marker {
+    ///     fn keys(&self) -> Vec<u32> {
+    ///         vec![1, 2]
+    ///     }
+    /// }
+    ///
+    /// let index = MultiKeyIndex::new(vec![Marker, Marker]);
+    /// assert_eq!(index.find_range(&1, &2).count(), 4);
+    /// assert_eq!(index.find_range_dedup(&1, &2).len(), 2);
+    /// ```
+    fn find_range_dedup<'i>(&'i self, start: &K, end: &K) -> Vec<&'i R> {
+        let mut seen = BTreeSet::new();
+
+        self.find_range(start, end)
+            .filter(|record| seen.insert(*record as *const R))
+            .collect()
+    }
+
+    /// Retrieve the first and last records in the key range defined by
+    /// `start` and `end`, both included.
+    ///
+    /// When the range holds a single record, it is returned twice. When
+    /// it is empty, `None` is returned.
+    ///
+    /// The default implementation walks through
+    /// [`find_range`](Indexed::find_range). Implementations over sorted
+    /// structures can override it with two searches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::Record;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Sample(u32, char);
+    ///
+    /// impl Record<u32> for Sample {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new(vec![
+    ///     Sample(4, 'c'),
+    ///     Sample(1, 'a'),
+    ///     Sample(2, 'b'),
+    ///     Sample(4, 'd'),
+    ///     Sample(9, 'e'),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     index.range_bounds_records(&2, &8),
+    ///     Some((&Sample(2, 'b'), &Sample(4, 'd')))
+    /// );
+    /// assert_eq!(
+    ///     index.range_bounds_records(&9, &9),
+    ///     Some((&Sample(9, 'e'), &Sample(9, 'e')))
+    /// );
+    /// assert_eq!(index.range_bounds_records(&5, &8), None);
+    /// assert_eq!(index.range_bounds_records(&8, &2), None);
+    /// ```
+    fn range_bounds_records<'i>(&'i self, start: &K, end: &K) -> Option<(&'i R, &'i R)> {
+        let mut records = self.find_range(start, end);
+        let first = records.next()?;
+
+        Some((first, records.last().unwrap_or(first)))
+    }
+
+    /// Retrieve all records matching the key, sorted by `cmp`.
+    ///
+    /// The sort is stable, so records comparing equal are kept in the
+    /// order returned by [`find`](Indexed::find).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Score {
+    ///     player: &'static str,
+    ///     level: u32,
+    ///     points: u64,
+    /// }
+    ///
+    /// impl Record<u32> for Score {
+    ///     fn key(&self) -> u32 {
+    ///         self.level
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new(vec![
+    ///     Score { player: "ann", level: 1, points: 30 },
+    ///     Score { player: "bob", level: 1, points: 90 },
+    ///     Score { player: "cid", level: 2, points: 70 },
+    ///     Score { player: "dee", level: 1, points: 50 },
+    /// ]);
+    ///
+    /// // Highest scores of the first level first.
+    /// let ranking = index
+    ///     .find_sorted_by(&1, |a, b| b.points.cmp(&a.points))
+    ///     .into_iter()
+    ///     .map(|s| s.player)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(ranking, vec!["bob", "dee", "ann"]);
+    /// ```
+    fn find_sorted_by<'i, C>(&'i self, key: &K, cmp: C) -> Vec<&'i R>
+    where
+        Self: Sized,
+        C: Fn(&R, &R) -> Ordering,
+    {
+        let mut records = self.find(key).collect::<Vec<_>>();
+        records.sort_by(|a, b| cmp(a, b));
+
+        records
+    }
+
+    /// Join the records of the index with those of `other` sharing their
+    /// key.
+    ///
+    /// Every pair of records with equal keys is returned, so a key held
+    /// by `n` records here and `m` in `other` produces `n * m` pairs. The
+    /// pairs are in key order, and for each key in the order of the
+    /// records of the index, then of `other`.
+    ///
+    /// Both indices are swept once, in key order, relying on the order of
+    /// [`find_range_bounds`](Indexed::find_range_bounds), so this is in
+    /// O(n + m + matches), and only holds the records of the current key
+    /// of each index besides the results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Order(u32, &'static str);
+    /// struct Shipment(u32, char);
+    ///
+    /// impl Record<u32> for Order {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// impl Record<u32> for Shipment {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let orders = SortedVecIndex::new(vec![
+    ///     Order(1, "a"),
+    ///     Order(2, "b"),
+    ///     Order(2, "c"),
+    ///     Order(4, "d"),
+    /// ]);
+    /// let shipments = SortedVecIndex::new(vec![
+    ///     Shipment(2, 'x'),
+    ///     Shipment(3, 'y'),
+    ///     Shipment(2, 'z'),
+    ///     Shipment(2, 'w'),
+    ///     Shipment(4, 'v'),
+    /// ]);
+    ///
+    /// let joined = orders
+    ///     .merge_join(&shipments)
+    ///     .into_iter()
+    ///     .map(|(o, s)| (o.1, s.1))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     joined,
+    ///     vec![
+    ///         ("b", 'x'),
+    ///         ("b", 'z'),
+    ///         ("b", 'w'),
+    ///         ("c", 'x'),
+    ///         ("c", 'z'),
+    ///         ("c", 'w'),
+    ///         ("d", 'v'),
+    ///     ]
+    /// );
+    /// ```
+    fn merge_join<'i, R2, I>(&'i self, other: &'i I) -> Vec<(&'i R, &'i R2)>
+    where
+        Self: Sized,
+        I: Indexed<R2, K>,
+        R: Record<K>,
+        R2: Record<K>,
+        K: Ord,
+    {
+        let mut left = self.find_range_bounds(..).peekable();
+        let mut right = other.find_range_bounds(..).peekable();
+        let mut joined = Vec::new();
+
+        while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+            let key = l.key();
+            match key.cmp(&r.key()) {
+                Ordering::Less => {
+                    left.next();
+                }
+                Ordering::Greater => {
+                    right.next();
+                }
+                Ordering::Equal => {
+                    let lefts =
+                        iter::from_fn(|| left.next_if(|l| l.key() == key)).collect::<Vec<_>>();
+                    let rights =
+                        iter::from_fn(|| right.next_if(|r| r.key() == key)).collect::<Vec<_>>();
+
+                    for l in &lefts {
+                        joined.extend(rights.iter().map(|r| (*l, *r)));
+                    }
+                }
+            }
+        }
+
+        joined
+    }
+}
+
+/// Error returned by [`Indexed::find_range_capped`] when a range holds
+/// too many records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeTooLarge {
+    /// Number of records in the range.
+    pub count: usize,
+    /// Maximum number of records allowed.
+    pub max: usize,
+}
+
+impl fmt::Display for RangeTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "range holds {} records, more than the maximum of {}",
+            self.count, self.max
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RangeTooLarge {}
+
+/// Violation of the invariants of an index, reported by the
+/// `verify_invariants` methods of the provided indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvariantError {
+    /// The key at `position` is greater than the key following it.
+    Unsorted {
+        /// Position of the key.
+        position: usize,
+    },
+    /// The record at `position` is not stored under its own keys.
+    KeyMismatch {
+        /// Position of the record.
+        position: usize,
+    },
+    /// The index refers to a record at `position`, which does not exist.
+    UnknownRecord {
+        /// Position of the record.
+        position: usize,
+    },
+    /// The index holds a different number of keys and records.
+    LengthMismatch {
+        /// Number of keys.
+        keys: usize,
+        /// Number of records.
+        records: usize,
+    },
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantError::Unsorted { position } => {
+                write!(f, "key at position {} is out of order", position)
+            }
+            InvariantError::KeyMismatch { position } => write!(
+                f,
+                "record at position {} is not stored under its keys",
+                position
+            ),
+            InvariantError::UnknownRecord { position } => {
+                write!(f, "no record at position {}", position)
+            }
+            InvariantError::LengthMismatch { keys, records } => {
+                write!(f, "{} keys for {} records", keys, records)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantError {}
+
+/// Methods provided by indices which can be queried with a borrowed
+/// form of their keys.
+///
+/// This mirrors `BTreeMap::get`, and allows for example to query an index
+/// on `String` keys with a `&str`, without allocating a `String` per
+/// lookup.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::IndexedBorrow;
+/// use ironsea_index::Record;
+///
+/// struct File {
+///     name: String,
+///     size: u64,
+/// }
+///
+/// impl Record<String> for File {
+///     fn key(&self) -> String {
+///         self.name.clone()
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![
+///     File { name: "b.txt".to_string(), size: 2 },
+///     File { name: "a.txt".to_string(), size: 1 },
+/// ]);
+///
+/// let sizes = index.find_borrowed("a.txt").map(|f| f.size).collect::<Vec<_>>();
+/// assert_eq!(sizes, vec![1]);
+/// ```
+pub trait IndexedBorrow<R, K>: Indexed<R, K> {
+    /// Retrieve all records matching the borrowed key.
+    ///
+    /// The ordering of `Q` must match the ordering of `K`.
+    fn find_borrowed<'i, Q>(&'i self, key: &Q) -> Box<dyn Iterator<Item = &'i R> + 'i>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized;
+}
+
+/// Methods returning owned copies of the records of an index.
+///
+/// This is implemented for every [`Indexed`] implementation whose records
+/// can be cloned, and is meant for callers who need to own the results,
+/// for example to send them across threads. Every record returned is
+/// cloned, so the borrowing methods of [`Indexed`] should be preferred
+/// whenever possible.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::Indexed;
+/// use ironsea_index::IndexedOwned;
+///
+/// let mut index = BTreeMap::new();
+/// index.insert(1, vec!["a".to_string(), "b".to_string()]);
+/// index.insert(2, vec!["c".to_string()]);
+///
+/// let owned: Vec<String> = index.find_owned(&1);
+/// let borrowed = index.find(&1).cloned().collect::<Vec<_>>();
+/// assert_eq!(owned, borrowed);
+///
+/// let owned: Vec<String> = index.find_range_owned(&1, &2);
+/// let borrowed = index.find_range(&1, &2).cloned().collect::<Vec<_>>();
+/// assert_eq!(owned, borrowed);
+/// ```
+pub trait IndexedOwned<R: Clone, K> {
+    /// Retrieve clones of all records matching the key.
+    fn find_owned(&self, key: &K) -> Vec<R>;
+
+    /// Retrieve clones of all records matching in the key range defined
+    /// by `start` and `end`, both included.
+    fn find_range_owned(&self, start: &K, end: &K) -> Vec<R>;
+}
+
+impl<T, R, K> IndexedOwned<R, K> for T
+where
+    T: Indexed<R, K> + ?Sized,
+    R: Clone,
+{
+    fn find_owned(&self, key: &K) -> Vec<R> {
+        self.find(key).cloned().collect()
+    }
+
+    fn find_range_owned(&self, start: &K, end: &K) -> Vec<R> {
+        self.find_range(start, end).cloned().collect()
+    }
+}
+
+/// Methods returning records which are borrowed when possible, and owned
+/// when they have to be built, for example by destructuring indices.
+///
+/// This is implemented for every [`Indexed`] implementation, returning
+/// borrowed records. Other indices can implement it to return owned
+/// records, so that callers can handle both through a single signature.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::CowIndex;
+/// use ironsea_index::IndexedDestructured;
+/// use ironsea_index::RecordBuilder;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Measurement {
+///     t: i64,
+///     temp: u32,
+/// }
+///
+/// impl RecordBuilder<Measurement, i64, u32> for Measurement {
+///     fn build(t: i64, temp: u32) -> Measurement {
+///         Measurement { t, temp }
+///     }
+/// }
+///
+/// // Destructuring index, storing the temperatures by timestamp.
+/// struct Index(Vec<(i64, u32)>);
+///
+/// # impl IndexedDestructured<u32, i64> for Index {
+/// #     fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+/// #         let key = *key;
+/// #         Box::new(self.0.iter().filter(move |(k, _)| *k == key).map(|(_, f)| f))
+/// #     }
+/// #
+/// #     fn find_range<'i>(
+/// #         &'i self,
+/// #         start: &i64,
+/// #         end: &i64,
+/// #     ) -> Box<dyn Iterator<Item = (i64, &'i u32)> + 'i> {
+/// #         let (start, end) = (*start, *end);
+/// #         Box::new(
+/// #             self.0
+/// #                 .iter()
+/// #                 .filter(move |(k, _)| start <= *k && *k <= end)
+/// #                 .map(|(k, f)| (*k, f)),
+/// #         )
+/// #     }
+/// # }
+/// #
+/// impl CowIndex<Measurement, i64> for Index {
+///     fn find_cow<'a>(&'a self, key: &i64) -> Vec<Cow<'a, Measurement>> {
+///         self.find_rebuilt::<Measurement, Measurement>(key)
+///             .into_iter()
+///             .map(Cow::Owned)
+///             .collect()
+///     }
+/// }
+///
+/// let mut map = BTreeMap::new();
+/// map.insert(1, vec![Measurement { t: 1, temp: 20 }]);
+/// let destructured = Index(vec![(1, 20)]);
+///
+/// let borrowed = map.find_cow(&1);
+/// let owned = destructured.find_cow(&1);
+///
+/// assert!(matches!(borrowed[0], Cow::Borrowed(_)));
+/// assert!(matches!(owned[0], Cow::Owned(_)));
+/// assert_eq!(borrowed, owned);
+/// ```
+pub trait CowIndex<R: Clone, K> {
+    /// Retrieve all records matching the key, borrowed when possible.
+    fn find_cow<'a>(&'a self, key: &K) -> Vec<Cow<'a, R>>;
+}
+
+impl<T, R, K> CowIndex<R, K> for T
+where
+    T: Indexed<R, K> + ?Sized,
+    R: Clone,
+{
+    fn find_cow<'a>(&'a self, key: &K) -> Vec<Cow<'a, R>> {
+        self.find(key).map(Cow::Borrowed).collect()
+    }
+}
+
+/// Methods provided by indices whose lookups are asynchronous, for
+/// example when the records are stored on disk or behind a network.
+///
+/// The records are returned by value, as the backing store might not
+/// hold them in memory for as long as the index is borrowed.
+///
+/// This trait uses `async fn` in traits, stable since Rust 1.75, below
+/// the minimum supported Rust version of this crate, 1.77. The returned
+/// futures have no `Send` bound, so they cannot be spawned on
+/// multi-threaded executors from generic code; implementors which need
+/// this can instead provide inherent methods returning
+/// `impl Future + Send`.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::sync::Arc;
+/// use std::task::Context;
+/// use std::task::Poll;
+/// use std::task::Wake;
+/// use std::task::Waker;
+///
+/// use ironsea_index::IndexedAsync;
+///
+/// struct Store(BTreeMap<u32, Vec<String>>);
+///
+/// impl IndexedAsync<String, u32> for Store {
+///     async fn find(&self, key: &u32) -> Vec<String> {
+///         // Stands for an actual I/O operation.
+///         std::future::ready(()).await;
+///         self.0.get(key).cloned().unwrap_or_default()
+///     }
+///
+///     async fn find_range(&self, start: &u32, end: &u32) -> Vec<String> {
+///         std::future::ready(()).await;
+///         self.0.range(start..=end).flat_map(|(_, v)| v.clone()).collect()
+///     }
+/// }
+///
+/// struct Noop;
+///
+/// impl Wake for Noop {
+///     fn wake(self: Arc<Self>) {}
+/// }
+///
+/// fn block_on<F: Future>(future: F) -> F::Output {
+///     let mut future = pin!(future);
+///     let waker = Waker::from(Arc::new(Noop));
+///     let mut context = Context::from_waker(&waker);
+///     loop {
+///         if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+///             return output;
+///         }
+///     }
+/// }
+///
+/// let mut map = BTreeMap::new();
+/// map.insert(1, vec!["a".to_string()]);
+/// map.insert(2, vec!["b".to_string(), "c".to_string()]);
+/// map.insert(3, vec!["d".to_string()]);
+/// let store = Store(map);
+///
+/// assert_eq!(block_on(store.find(&2)), vec!["b", "c"]);
+/// assert_eq!(block_on(store.find(&4)), Vec::<String>::new());
+/// assert_eq!(block_on(store.find_range(&2, &3)), vec!["b", "c", "d"]);
+/// ```
+#[allow(async_fn_in_trait)]
+pub trait IndexedAsync<R, K> {
+    /// Retrieve all records matching the key.
+    async fn find(&self, key: &K) -> Vec<R>;
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, in key order.
+    async fn find_range(&self, start: &K, end: &K) -> Vec<R>;
+}
+
+/// Methods provided by indices whose lookups can fail, for example when
+/// the records are read from a memory-mapped file or a remote store.
+///
+/// Every [`Indexed`] implementation is also a `TryIndexed`, whose
+/// methods never fail, with [`Infallible`] as error type. Code written
+/// against `TryIndexed` thus accepts both kinds of indices, and can
+/// propagate the errors with the `?` operator.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::TryIndexed;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Poisoned;
+///
+/// struct Store {
+///     poisoned: bool,
+///     records: Vec<(u32, char)>,
+/// }
+///
+/// impl TryIndexed<(u32, char), u32> for Store {
+///     type Error = Poisoned;
+///
+///     fn try_find<'i>(
+///         &'i self,
+///         key: &u32,
+///     ) -> Result<Box<dyn Iterator<Item = &'i (u32, char)> + 'i>, Poisoned> {
+///         self.try_find_range(key, key)
+///     }
+///
+///     fn try_find_range<'i>(
+///         &'i self,
+///         start: &u32,
+///         end: &u32,
+///     ) -> Result<Box<dyn Iterator<Item = &'i (u32, char)> + 'i>, Poisoned> {
+///         if self.poisoned {
+///             return Err(Poisoned);
+///         }
+///         let (start, end) = (*start, *end);
+///         Ok(Box::new(
+///             self.records.iter().filter(move |r| start <= r.0 && r.0 <= end),
+///         ))
+///     }
+/// }
+///
+/// // Generic code propagates the errors of the index.
+/// fn count<I: TryIndexed<(u32, char), u32>>(index: &I, key: u32) -> Result<usize, I::Error> {
+///     Ok(index.try_find(&key)?.count())
+/// }
+///
+/// let mut store = Store {
+///     poisoned: false,
+///     records: vec![(1, 'a'), (1, 'b'), (2, 'c')],
+/// };
+/// assert_eq!(count(&store, 1), Ok(2));
+///
+/// store.poisoned = true;
+/// assert_eq!(count(&store, 1), Err(Poisoned));
+///
+/// // Infallible indices can be used in the same way.
+/// let mut map = BTreeMap::new();
+/// map.insert(1, vec![(1, 'a'), (1, 'b')]);
+/// assert_eq!(count(&map, 1), Ok(2));
+/// ```
+pub trait TryIndexed<R, K> {
+    /// Error returned when the index cannot be accessed.
+    type Error;
+
+    /// Retrieve all records matching the key.
+    fn try_find<'i>(&'i self, key: &K)
+        -> Result<Box<dyn Iterator<Item = &'i R> + 'i>, Self::Error>;
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included.
+    fn try_find_range<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+    ) -> Result<Box<dyn Iterator<Item = &'i R> + 'i>, Self::Error>;
+}
+
+impl<T, R, K> TryIndexed<R, K> for T
+where
+    T: Indexed<R, K> + ?Sized,
+{
+    type Error = Infallible;
+
+    fn try_find<'i>(&'i self, key: &K) -> Result<Box<dyn Iterator<Item = &'i R> + 'i>, Infallible> {
+        Ok(self.find(key))
+    }
+
+    fn try_find_range<'i>(
+        &'i self,
+        start: &K,
+        end: &K,
+    ) -> Result<Box<dyn Iterator<Item = &'i R> + 'i>, Infallible> {
+        Ok(self.find_range(start, end))
+    }
+}
+
+/// Methods provided by secondary indices, mapping a secondary key to the
+/// primary keys of the matching records.
+///
+/// Looking up a record by secondary key is done in two hops: the
+/// secondary index resolves the primary keys, which are then looked up
+/// in the primary index, see
+/// [`find_by_secondary`](SecondaryIndex::find_by_secondary).
+///
+/// Both indices must be kept consistent: every primary key returned by
+/// the secondary index must be stored in the primary index, and every
+/// record of the primary index must be reachable from its secondary
+/// key. Primary keys missing from the primary index are skipped.
+///
+///  * `R`: Type of the records
+///  * `K2`: Type of the secondary keys
+///  * `K1`: Type of the primary keys
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Record;
+/// use ironsea_index::SecondaryIndex;
+///
+/// struct User {
+///     id: u32,
+///     email: &'static str,
+/// }
+///
+/// impl Record<u32> for User {
+///     fn key(&self) -> u32 {
+///         self.id
+///     }
+/// }
+///
+/// struct ByEmail(BTreeMap<&'static str, Vec<u32>>);
+///
+/// impl SecondaryIndex<User, &'static str, u32> for ByEmail {
+///     fn resolve(&self, email: &&'static str) -> Vec<u32> {
+///         self.0.get(email).cloned().unwrap_or_default()
+///     }
+/// }
+///
+/// let users = vec![
+///     User { id: 2, email: "bob@example.com" },
+///     User { id: 1, email: "alice@example.com" },
+/// ];
+///
+/// let mut by_email = BTreeMap::new();
+/// for user in &users {
+///     by_email.entry(user.email).or_insert_with(Vec::new).push(user.id);
+/// }
+/// let by_email = ByEmail(by_email);
+/// let by_id = SortedVecIndex::new(users);
+///
+/// let found = by_email.find_by_secondary(&by_id, &"bob@example.com");
+/// assert_eq!(found.map(|u| u.id).collect::<Vec<_>>(), vec![2]);
+///
+/// assert_eq!(by_email.find_by_secondary(&by_id, &"eve@example.com").count(), 0);
+/// ```
+pub trait SecondaryIndex<R, K2, K1> {
+    /// Retrieve the primary keys of the records matching the secondary
+    /// key.
+    fn resolve(&self, key: &K2) -> Vec<K1>;
+
+    /// Retrieve all records matching the secondary key, by looking up in
+    /// `primary` the primary keys it resolves to.
+    ///
+    /// The records are returned in the order of the primary keys
+    /// returned by [`resolve`](SecondaryIndex::resolve).
+    fn find_by_secondary<'i, P>(
+        &self,
+        primary: &'i P,
+        key: &K2,
+    ) -> Box<dyn Iterator<Item = &'i R> + 'i>
+    where
+        P: Indexed<R, K1>,
+        K1: 'i,
+    {
+        Box::new(
+            self.resolve(key)
+                .into_iter()
+                .flat_map(move |key| primary.find(&key)),
+        )
+    }
+}
+
+/// Methods provided by indices storing their records contiguously, in
+/// key order, such as sorted arrays.
+///
+/// Range queries on such indices can be answered with a slice of the
+/// backing store, avoiding any iterator or allocation.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::ContiguousIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Reading(u32, f32);
+///
+/// impl Record<u32> for Reading {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![Reading(3, 0.3), Reading(1, 0.1), Reading(2, 0.2)]);
+///
+/// let slice = index.find_range_slice(&2, &3).unwrap();
+/// assert_eq!(slice, &[Reading(2, 0.2), Reading(3, 0.3)]);
+/// assert_eq!(slice.len(), index.find_range(&2, &3).count());
+///
+/// assert_eq!(index.find_range_slice(&4, &5), Some(&[][..]));
+/// ```
+pub trait ContiguousIndex<R, K>: Indexed<R, K> {
+    /// Retrieve the slice of the records matching in the key range
+    /// defined by `start` and `end`, both included, in key order.
+    ///
+    /// `None` is returned when the matching records cannot be provided
+    /// as a single slice, in which case
+    /// [`find_range`](Indexed::find_range) should be used instead. An
+    /// empty range returns `Some` empty slice.
+    fn find_range_slice(&self, start: &K, end: &K) -> Option<&[R]>;
+
+    /// Position of the first record whose key is not less than `key`,
+    /// in key order, as `slice::partition_point` would return.
+    ///
+    /// All the records before this position have a key less than `key`,
+    /// and `upper_bound(key) - lower_bound(key)` is equal to
+    /// [`count(key)`](Indexed::count).
+    ///
+    /// The default implementation counts the records before `key`, and
+    /// is therefore in O(n). Implementations are encouraged to override
+    /// it with a binary search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::ContiguousIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Item(u32);
+    ///
+    /// impl Record<u32> for Item {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new([1, 2, 2, 2, 4].iter().copied().map(Item).collect());
+    ///
+    /// assert_eq!(index.lower_bound(&2), 1);
+    /// assert_eq!(index.upper_bound(&2), 4);
+    /// assert_eq!((index.lower_bound(&3), index.upper_bound(&3)), (4, 4));
+    /// assert_eq!((index.lower_bound(&9), index.upper_bound(&0)), (5, 0));
+    ///
+    /// for key in 0..6 {
+    ///     let bounds = index.upper_bound(&key) - index.lower_bound(&key);
+    ///     assert_eq!(bounds, index.count(&key));
+    /// }
+    /// ```
+    fn lower_bound(&self, key: &K) -> usize {
+        self.find_bounds(Bound::Unbounded, Bound::Excluded(key))
+            .count()
+    }
+
+    /// Position following the last record whose key is not greater than
+    /// `key`, in key order, as `slice::partition_point` would return.
+    ///
+    /// All the records before this position have a key less than or
+    /// equal to `key`. See [`lower_bound`](ContiguousIndex::lower_bound).
+    fn upper_bound(&self, key: &K) -> usize {
+        self.find_bounds(Bound::Unbounded, Bound::Included(key))
+            .count()
+    }
+}
+
+/// Access to the records backing an array-based index, in key order.
+///
+/// This allows handing the records over to other libraries, for example
+/// for serialisation, without copying them or going through
+/// [`Indexed`]. The slice reflects the state of the index when it is
+/// borrowed, and cannot outlive a modification of the index.
+///
+///  * `R`: Type of the records
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::AsRecordSlice;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Reading(u32, f32);
+///
+/// impl Record<u32> for Reading {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![Reading(3, 0.3), Reading(1, 0.1), Reading(2, 0.2)]);
+///
+/// let slice = index.as_record_slice();
+/// assert_eq!(
+///     slice.iter().collect::<Vec<_>>(),
+///     index.find_range_bounds(..).collect::<Vec<_>>()
+/// );
+/// ```
+pub trait AsRecordSlice<R> {
+    /// Retrieve all the records of the index, in key order.
+    fn as_record_slice(&self) -> &[R];
+
+    /// Retrieve a uniform random sample of `n` records, without
+    /// replacement, in key order.
+    ///
+    /// When `n` is larger than the number of records, all the records
+    /// are returned.
+    ///
+    /// The randomness is provided by `below`, which must return a
+    /// uniformly distributed integer lower than its argument, for
+    /// example `|bound| rng.gen_range(0..bound)` with the `rand` crate.
+    /// It is called `n` times, following Floyd's algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::AsRecordSlice;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Item(u32);
+    ///
+    /// impl Record<u32> for Item {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// // Seeded xorshift generator.
+    /// fn rng(mut state: u64) -> impl FnMut(usize) -> usize {
+    ///     move |bound| {
+    ///         state ^= state << 13;
+    ///         state ^= state >> 7;
+    ///         state ^= state << 17;
+    ///         (state % bound as u64) as usize
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new((0..100).map(Item).collect());
+    ///
+    /// let sample = index.sample(10, rng(42)).iter().map(|r| r.0).collect::<Vec<_>>();
+    /// let again = index.sample(10, rng(42)).iter().map(|r| r.0).collect::<Vec<_>>();
+    /// assert_eq!(sample, again);
+    ///
+    /// // Distinct records, in key order.
+    /// assert_eq!(sample.len(), 10);
+    /// assert!(sample.windows(2).all(|w| w[0] < w[1]));
+    ///
+    /// assert_eq!(index.sample(1000, rng(42)).len(), 100);
+    /// ```
+    fn sample<F>(&self, n: usize, mut below: F) -> Vec<&R>
+    where
+        F: FnMut(usize) -> usize,
+    {
+        let records = self.as_record_slice();
+        if n >= records.len() {
+            return records.iter().collect();
+        }
+
+        let mut chosen = BTreeSet::new();
+        for bound in records.len() - n..records.len() {
+            let position = below(bound + 1);
+            if !chosen.insert(position) {
+                chosen.insert(bound);
+            }
+        }
+
+        chosen
+            .into_iter()
+            .map(|position| &records[position])
+            .collect()
+    }
+}
+
+/// Index returning the positions of its records, instead of references
+/// to them.
+///
+/// Positions are 0-based, and refer to the array of records the index
+/// was built over, in its original order. The index can therefore be
+/// decoupled from the storage of the records, for example to look up the
+/// rows of a columnar table, whose columns are kept in parallel arrays.
+///
+/// Positions are returned in the same order as the records of the
+/// corresponding [`Indexed`] queries, when the index implements it.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::MultiKeyIndex;
+/// use ironsea_index::PositionalIndex;
+/// use ironsea_index::Record;
+///
+/// #[derive(Clone)]
+/// struct Row {
+///     year: u32,
+/// }
+///
+/// impl Record<u32> for Row {
+///     fn key(&self) -> u32 {
+///         self.year
+///     }
+/// }
+///
+/// let rows = vec![Row { year: 2021 }, Row { year: 2019 }, Row { year: 2021 }];
+/// let amounts = vec![10, 20, 30];
+///
+/// let index = MultiKeyIndex::new(rows.clone());
+///
+/// let positions = index.find_positions(&2021);
+/// assert_eq!(positions, vec![0, 2]);
+/// assert!(positions.iter().all(|p| rows[*p].year == 2021));
+///
+/// let total = index
+///     .find_range_positions(&2019, &2020)
+///     .into_iter()
+///     .map(|p| amounts[p])
+///     .sum::<u32>();
+/// assert_eq!(total, 20);
+/// ```
+pub trait PositionalIndex<K> {
+    /// Retrieve the positions of all records matching the key.
+    fn find_positions(&self, key: &K) -> Vec<usize>;
+
+    /// Retrieve the positions of all records matching in the key range
+    /// defined by `start` and `end`, both included.
+    fn find_range_positions(&self, start: &K, end: &K) -> Vec<usize>;
+}
+
+/// Methods provided by indices storing the keys of their records, to
+/// return each record along with the key it matched under.
+///
+/// The key returned is the key of the index the record was found under.
+/// For records with a single key this is what [`Record::key`] returns,
+/// but for records stored under multiple keys, as with [`RecordKeys`],
+/// it can be any of them, and a record matching under multiple keys of
+/// the range is returned once for each of them.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::MultiKeyIndex;
+/// use ironsea_index::IndexedWithKeys;
+/// use ironsea_index::RecordKeys;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Range(u32, u32);
+///
+/// impl RecordKeys<u32> for Range {
+///     fn keys(&self) -> Vec<u32> {
+///         (self.0..self.1).collect()
+///     }
+/// }
+///
+/// let index = MultiKeyIndex::new(vec![Range(0, 2), Range(5, 6)]);
+///
+/// assert_eq!(
+///     index.find_range_with_keys(&1, &5),
+///     vec![(&1, &Range(0, 2)), (&5, &Range(5, 6))]
+/// );
+/// assert_eq!(
+///     index.find_range_with_keys(&0, &1),
+///     vec![(&0, &Range(0, 2)), (&1, &Range(0, 2))]
+/// );
+/// ```
+pub trait IndexedWithKeys<R, K>: Indexed<R, K> {
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, in key order, each with the key
+    /// it matched under.
+    fn find_range_with_keys<'i>(&'i self, start: &K, end: &K) -> Vec<(&'i K, &'i R)>;
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`, both included, grouped by key.
+    ///
+    /// Each distinct key of the range is returned once, in key order,
+    /// with its records in the order returned by
+    /// [`find`](Indexed::find).
+    ///
+    /// The default implementation groups the adjacent equal keys
+    /// returned by [`find_range_with_keys`], and so relies on its
+    /// results being in key order.
+    ///
+    /// [`find_range_with_keys`]: IndexedWithKeys::find_range_with_keys
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::IndexedWithKeys;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Sale(u32, char);
+    ///
+    /// impl Record<u32> for Sale {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new(vec![
+    ///     Sale(2, 'b'),
+    ///     Sale(1, 'a'),
+    ///     Sale(3, 'd'),
+    ///     Sale(2, 'c'),
+    ///     Sale(3, 'e'),
+    ///     Sale(3, 'f'),
+    ///     Sale(4, 'g'),
+    /// ]);
+    ///
+    /// let groups = index
+    ///     .find_range_grouped(&1, &3)
+    ///     .into_iter()
+    ///     .map(|(k, sales)| (*k, sales.iter().map(|s| s.1).collect::<String>()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     groups,
+    ///     vec![(1, "a".to_string()), (2, "bc".to_string()), (3, "def".to_string())]
+    /// );
+    ///
+    /// assert!(index.find_range_grouped(&5, &9).is_empty());
+    /// ```
+    fn find_range_grouped<'i>(&'i self, start: &K, end: &K) -> Vec<(&'i K, Vec<&'i R>)>
+    where
+        K: PartialEq,
+    {
+        let mut groups = Vec::<(&K, Vec<&R>)>::new();
+        for (key, record) in self.find_range_with_keys(start, end) {
+            match groups.last_mut() {
+                Some((last, records)) if *last == key => records.push(record),
+                _ => groups.push((key, vec![record])),
+            }
+        }
+
+        groups
+    }
+}
+
+/// Methods provided by indices on string keys, to retrieve the records
+/// whose key starts with a given prefix.
+///
+/// On a sorted structure, the matching keys are contiguous: they start
+/// at the first key greater or equal to the prefix, and end before the
+/// first following key which does not start with the prefix. This avoids
+/// computing an upper bound by "incrementing" the prefix, which is
+/// ill-defined for strings.
+///
+/// Prefixes are matched on Unicode scalar values, exactly as
+/// `str::starts_with` does, and not on grapheme clusters. No
+/// normalisation is applied either, so a precomposed `é` does not match
+/// an `e` followed by a combining accent.
+///
+/// An empty prefix matches every record.
+///
+///  * `R`: Type of the records
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::PrefixIndex;
+/// use ironsea_index::Record;
+///
+/// struct File(&'static str);
+///
+/// impl Record<String> for File {
+///     fn key(&self) -> String {
+///         self.0.to_string()
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![
+///     File("src/lib.rs"),
+///     File("README.md"),
+///     File("src/impls.rs"),
+///     File("src_old/lib.rs"),
+/// ]);
+///
+/// let found = |prefix| index.find_prefix(prefix).map(|f| f.0).collect::<Vec<_>>();
+///
+/// assert_eq!(found("src/"), vec!["src/impls.rs", "src/lib.rs"]);
+/// assert_eq!(found("src"), vec!["src/impls.rs", "src/lib.rs", "src_old/lib.rs"]);
+/// assert_eq!(found("target/"), Vec::<&str>::new());
+/// assert_eq!(found("").len(), 4);
+/// ```
+pub trait PrefixIndex<R> {
+    /// Retrieve all records whose key starts with `prefix`, in key
+    /// order.
+    fn find_prefix<'i>(&'i self, prefix: &str) -> Box<dyn Iterator<Item = &'i R> + 'i>;
+}
+
+/// Methods provided by indices on string keys, to retrieve the records
+/// whose key is close to a given string.
+///
+/// Closeness is measured with the Levenshtein distance, the number of
+/// single character insertions, deletions or substitutions needed to
+/// transform a key into the other. As for [`PrefixIndex`], characters
+/// are Unicode scalar values.
+///
+///  * `R`: Type of the records
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::FuzzyIndex;
+/// use ironsea_index::Record;
+///
+/// struct Word(&'static str);
+///
+/// impl Record<String> for Word {
+///     fn key(&self) -> String {
+///         self.0.to_string()
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![Word("colour"), Word("color"), Word("cool"), Word("dolor")]);
+///
+/// let found = |key, distance| {
+///     index
+///         .find_fuzzy(key, distance)
+///         .into_iter()
+///         .map(|(w, d)| (w.0, d))
+///         .collect::<Vec<_>>()
+/// };
+///
+/// assert_eq!(found("colour", 0), vec![("colour", 0)]);
+/// assert_eq!(found("colour", 1), vec![("colour", 0), ("color", 1)]);
+/// assert_eq!(found("color", 2), vec![("color", 0), ("colour", 1), ("dolor", 1), ("cool", 2)]);
+/// ```
+pub trait FuzzyIndex<R>: Indexed<R, String> + KeyEnumerable<String> {
+    /// Retrieve all records whose key is within `max_distance` of `key`,
+    /// along with the distance of their key.
+    ///
+    /// Records are returned by ascending distance, then in key order. A
+    /// `max_distance` of 0 only returns the records matching exactly.
+    ///
+    /// The default implementation computes the distance to every
+    /// distinct key, so it is in O(n·m), for n keys of length m.
+    /// Implementations are encouraged to override it with dedicated
+    /// structures, such as BK-trees.
+    fn find_fuzzy(&self, key: &str, max_distance: usize) -> Vec<(&R, usize)> {
+        let mut matches = self
+            .distinct_keys()
+            .filter_map(|k| levenshtein(key, k, max_distance).map(|d| (k, d)))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(_, d)| *d);
+
+        matches
+            .into_iter()
+            .flat_map(|(k, d)| self.find(k).map(move |r| (r, d)))
+            .collect()
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, or `None` if it is greater
+/// than `max`.
+fn levenshtein(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    // Distances from the prefixes of `a` to the current prefix of `b`.
+    let mut row = (0..=a.len()).collect::<Vec<_>>();
+    for (j, cb) in b.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = j + 1;
+        for (i, ca) in a.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[i + 1];
+            row[i + 1] = substitution.min(row[i] + 1).min(diagonal + 1);
+        }
+
+        // Distances can only grow from one row to the next.
+        if row.iter().all(|d| *d > max) {
+            return None;
+        }
+    }
+
+    Some(row[a.len()]).filter(|d| *d <= max)
+}
+
+/// Methods provided by indices keeping multiple versions of the records
+/// of each key, to retrieve them as they were at a given version.
+///
+/// The version of a record is extracted with its [`Record`]
+/// implementation for the version type, which therefore has to differ
+/// from the type of the keys; a newtype can be used when both would be
+/// the same.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///  * `V`: Type of the versions, for example timestamps
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Record;
+/// use ironsea_index::TemporalIndex;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Version(u64);
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Price {
+///     item: u32,
+///     since: Version,
+///     cents: u32,
+/// }
+///
+/// impl Record<u32> for Price {
+///     fn key(&self) -> u32 {
+///         self.item
+///     }
+/// }
+///
+/// impl Record<Version> for Price {
+///     fn key(&self) -> Version {
+///         self.since
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![
+///     Price { item: 1, since: Version(10), cents: 100 },
+///     Price { item: 1, since: Version(30), cents: 120 },
+///     Price { item: 1, since: Version(20), cents: 110 },
+/// ]);
+///
+/// let cents = |version| index.find_as_of(&1, &Version(version)).map(|p| p.cents);
+///
+/// assert_eq!(cents(5), None);
+/// assert_eq!(cents(10), Some(100));
+/// assert_eq!(cents(25), Some(110));
+/// assert_eq!(cents(30), Some(120));
+/// assert_eq!(cents(99), Some(120));
+/// ```
+pub trait TemporalIndex<R, K, V: Ord>: Indexed<R, K>
+where
+    R: Record<V>,
+{
+    /// Retrieve the record of `key` as of `version`, that is the record
+    /// with the greatest version lower or equal to `version`.
+    ///
+    /// `None` is returned when the key has no record, or only records
+    /// with greater versions. When multiple records share the selected
+    /// version, the last one returned by [`Indexed::find`] is selected.
+    ///
+    /// The default implementation walks through all the versions of the
+    /// key, in O(versions).
+    fn find_as_of(&self, key: &K, version: &V) -> Option<&R> {
+        self.find(key)
+            .filter(|r| r.key() <= *version)
+            .max_by_key(|r| r.key())
+    }
+}
+
+/// Methods provided by indices storing at most one record per key.
+///
+/// Implementing this trait is a promise that no two records of the index
+/// share the same key, for example when indexing on a primary key.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::Bound;
+/// use std::ops::RangeBounds;
+///
+/// use ironsea_index::Indexed;
+/// use ironsea_index::UniqueIndex;
+///
+/// struct Index(Vec<(i64, u32)>);
+///
+/// # impl Indexed<(i64, u32), i64> for Index {
+/// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+/// #        let key = *key;
+/// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+/// #    }
+/// #
+/// #    fn find_bounds<'i>(
+/// #        &'i self,
+/// #        start: Bound<&i64>,
+/// #        end: Bound<&i64>,
+/// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+/// #        let range = (start.cloned(), end.cloned());
+/// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+/// #    }
+/// # }
+/// #
+/// impl UniqueIndex<(i64, u32), i64> for Index {}
+///
+/// let index = Index(vec![(1, 10), (2, 20)]);
+/// assert_eq!(index.find_one(&1), Some(&(1, 10)));
+/// assert_eq!(index.find_one(&3), None);
+///
+/// // Breaking the promise is detected in debug builds.
+/// if cfg!(debug_assertions) {
+///     let broken = Index(vec![(1, 10), (1, 11)]);
+///     assert!(std::panic::catch_unwind(|| broken.find_one(&1)).is_err());
+/// }
+/// ```
+pub trait UniqueIndex<R, K>: Indexed<R, K> {
+    /// Retrieve the record matching the key, if any.
+    ///
+    /// The default implementation returns the first record of
+    /// [`find`](Indexed::find), and checks in debug builds that there
+    /// is no other.
+    fn find_one(&self, key: &K) -> Option<&R> {
+        let mut records = self.find(key);
+        let record = records.next();
+
+        debug_assert!(
+            records.next().is_none(),
+            "UniqueIndex contains multiple records for the same key"
+        );
+
+        record
+    }
+}
+
+/// Methods provided by indices keeping their keys ordered.
+///
+/// This is kept separate from [`Indexed`], as some indices, for example
+/// hash-based ones, cannot answer these queries efficiently.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::Bound;
+/// use std::ops::RangeBounds;
+///
+/// use ironsea_index::Indexed;
+/// use ironsea_index::OrderedIndex;
+///
+/// // Records are kept sorted by key.
+/// struct Index(Vec<(i64, u32)>);
+///
+/// # impl Indexed<(i64, u32), i64> for Index {
+/// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+/// #        let key = *key;
+/// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+/// #    }
+/// #
+/// #    fn find_bounds<'i>(
+/// #        &'i self,
+/// #        start: Bound<&i64>,
+/// #        end: Bound<&i64>,
+/// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+/// #        let range = (start.cloned(), end.cloned());
+/// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+/// #    }
+/// # }
+/// #
+/// impl OrderedIndex<(i64, u32), i64> for Index {
+///     fn first_key(&self) -> Option<&i64> {
+///         self.0.first().map(|r| &r.0)
+///     }
+///
+///     fn last_key(&self) -> Option<&i64> {
+///         self.0.last().map(|r| &r.0)
+///     }
+/// }
+///
+/// let index = Index(vec![(1, 10), (1, 11), (4, 40), (4, 41)]);
+/// assert_eq!(index.first_key(), Some(&1));
+/// assert_eq!(index.last_key(), Some(&4));
+///
+/// // The first record of the extreme keys is returned.
+/// assert_eq!(index.min(), Some(&(1, 10)));
+/// assert_eq!(index.max(), Some(&(4, 40)));
+///
+/// let empty = Index(vec![]);
+/// assert_eq!(empty.first_key(), None);
+/// assert_eq!(empty.last_key(), None);
+/// assert_eq!(empty.min(), None);
+/// assert_eq!(empty.max(), None);
+/// ```
+pub trait OrderedIndex<R, K>: Indexed<R, K>
+where
+    K: Ord,
+{
+    /// Retrieve the smallest key stored in the index, or `None` if the
+    /// index is empty.
+    ///
+    /// This must be the smallest key for which [`Indexed::find`]
+    /// returns at least one record.
+    fn first_key(&self) -> Option<&K>;
+
+    /// Retrieve the largest key stored in the index, or `None` if the
+    /// index is empty.
+    ///
+    /// This must be the largest key for which [`Indexed::find`]
+    /// returns at least one record.
+    fn last_key(&self) -> Option<&K>;
+
+    /// Retrieve the first record stored under the smallest key, or
+    /// `None` if the index is empty.
+    ///
+    /// When multiple records share this key, the first one returned by
+    /// [`Indexed::find`] is selected, which for the indices provided by
+    /// this crate is the first one inserted.
+    fn min(&self) -> Option<&R> {
+        self.first_key().and_then(|key| self.find(key).next())
+    }
+
+    /// Retrieve the first record stored under the largest key, or `None`
+    /// if the index is empty.
+    ///
+    /// When multiple records share this key, the first one returned by
+    /// [`Indexed::find`] is selected, which for the indices provided by
+    /// this crate is the first one inserted.
+    fn max(&self) -> Option<&R> {
+        self.last_key().and_then(|key| self.find(key).next())
+    }
+
+    /// Iterate over the pairs of consecutive records, in key order.
+    ///
+    /// This is the counterpart of `slice::windows(2)` over all the
+    /// records of the index, as returned by
+    /// [`find_range`](Indexed::find_range) from the first to the last
+    /// key. Records sharing a key are paired with each other, and the
+    /// last record of a key is paired with the first record of the next
+    /// key. An index with fewer than two records returns no pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::OrderedIndex;
+    /// use ironsea_index::Record;
+    ///
+    /// // Odometer readings, by day.
+    /// struct Reading {
+    ///     day: u32,
+    ///     km: u32,
+    /// }
+    ///
+    /// impl Record<u32> for Reading {
+    ///     fn key(&self) -> u32 {
+    ///         self.day
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new(vec![
+    ///     Reading { day: 3, km: 180 },
+    ///     Reading { day: 1, km: 100 },
+    ///     Reading { day: 2, km: 150 },
+    /// ]);
+    ///
+    /// let deltas = index.windows_by_key().map(|(a, b)| b.km - a.km).collect::<Vec<_>>();
+    /// assert_eq!(deltas, vec![50, 30]);
+    /// ```
+    fn windows_by_key<'i>(&'i self) -> Box<dyn Iterator<Item = (&'i R, &'i R)> + 'i> {
+        let mut records = match (self.first_key(), self.last_key()) {
+            (Some(first), Some(last)) => self.find_range(first, last),
+            _ => return Box::new(iter::empty()),
+        };
+
+        let mut previous = records.next();
+        Box::new(iter::from_fn(move || {
+            let current = records.next()?;
+            let pair = (previous?, current);
+            previous = Some(current);
+            Some(pair)
+        }))
+    }
+}
+
+/// Total order on keys, used by indices to sort them.
+///
+/// This allows indexing keys which do not implement `Ord`, such as
+/// floating-point numbers, or ordering keys differently from their `Ord`
+/// implementation, for example in reverse. The order must be total and
+/// consistent across calls, otherwise the results of the indices are
+/// unspecified.
+///
+/// Closures comparing two keys implement this trait, while
+/// [`DefaultOrd`] uses the `Ord` implementation of the keys.
+///
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+///
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::Record;
+///
+/// struct Point {
+///     x: f64,
+///     name: &'static str,
+/// }
+///
+/// impl Record<f64> for Point {
+///     fn key(&self) -> f64 {
+///         self.x
+///     }
+/// }
+///
+/// let points = vec![
+///     Point { x: 2.5, name: "b" },
+///     Point { x: -1.0, name: "a" },
+///     Point { x: 7.25, name: "c" },
+/// ];
+/// let index = SortedVecIndex::with_order(points, |a: &f64, b: &f64| a.total_cmp(b));
+///
+/// let names = index.find_range(&-5.0, &5.0).map(|p| p.name).collect::<Vec<_>>();
+/// assert_eq!(names, vec!["a", "b"]);
+/// assert_eq!(index.find(&7.25).count(), 1);
+///
+/// // Reverse order, on keys implementing `Ord`.
+/// struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let items = vec![Item(1, 'a'), Item(3, 'c'), Item(2, 'b')];
+/// let index = SortedVecIndex::with_order(items, |a: &u32, b: &u32| b.cmp(a));
+///
+/// // Ranges go from the first to the last key in this order.
+/// let found = index.find_range(&3, &2).map(|r| r.1).collect::<Vec<_>>();
+/// assert_eq!(found, vec!['c', 'b']);
+/// ```
+pub trait KeyOrd<K> {
+    /// Compare two keys.
+    fn cmp(&self, a: &K, b: &K) -> Ordering;
+}
+
+/// Order of the keys given by their `Ord` implementation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultOrd;
+
+impl<K: Ord> KeyOrd<K> for DefaultOrd {
+    fn cmp(&self, a: &K, b: &K) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<K, F> KeyOrd<K> for F
+where
+    F: Fn(&K, &K) -> Ordering,
+{
+    fn cmp(&self, a: &K, b: &K) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// Distance between two keys.
+///
+/// This is used by [`NearestIndex`] to compare how close keys are to each
+/// other, as the notion of distance depends on the key type.
+pub trait KeyDistance {
+    /// Type of the distance between two keys.
+    type Dist: Ord;
+
+    /// Compute the distance between `self` and `other`.
+    fn distance(&self, other: &Self) -> Self::Dist;
+}
+
+macro_rules! impl_key_distance {
+    ($($t:ty => $d:ty),*) => {
+        $(
+            impl KeyDistance for $t {
+                type Dist = $d;
+
+                fn distance(&self, other: &Self) -> Self::Dist {
+                    self.abs_diff(*other)
+                }
+            }
+        )*
+    };
+}
+
+impl_key_distance!(
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize,
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize
+);
+
+/// Methods provided by indices of records spanning a range of keys, as
+/// given by [`RecordInterval`].
+///
+/// A record overlaps a query when its interval intersects the interval
+/// of the query, bounds included: the record interval may contain the
+/// query, be contained by it, or only partially overlap it.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedIntervalIndex;
+/// use ironsea_index::IntervalIndex;
+/// use ironsea_index::RecordInterval;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Booking(&'static str, u32, u32);
+///
+/// impl RecordInterval<u32> for Booking {
+///     fn interval(&self) -> (u32, u32) {
+///         (self.1, self.2)
+///     }
+/// }
+///
+/// let index = SortedIntervalIndex::new(vec![
+///     Booking("long", 0, 100),
+///     Booking("early", 10, 20),
+///     Booking("late", 50, 60),
+///     Booking("point", 30, 30),
+/// ]);
+///
+/// let names = |start, end| {
+///     index
+///         .find_overlapping(&start, &end)
+///         .into_iter()
+///         .map(|b| b.0)
+///         .collect::<Vec<_>>()
+/// };
+///
+/// // Query contained in records.
+/// assert_eq!(names(12, 15), vec!["long", "early"]);
+/// // Records contained in the query.
+/// assert_eq!(names(25, 65), vec!["long", "point", "late"]);
+/// // Partial overlaps, bounds included.
+/// assert_eq!(names(20, 30), vec!["long", "early", "point"]);
+/// assert_eq!(names(60, 200), vec!["long", "late"]);
+/// // Disjoint from every record.
+/// assert!(names(101, 200).is_empty());
+/// // Empty query.
+/// assert!(names(20, 10).is_empty());
+/// ```
+pub trait IntervalIndex<R, K: Ord> {
+    /// Retrieve all records whose interval overlaps the interval defined
+    /// by `start` and `end`, both included.
+    ///
+    /// No record overlaps an empty interval, where `start` is greater
+    /// than `end`.
+    fn find_overlapping(&self, start: &K, end: &K) -> Vec<&R>;
+}
+
+/// Methods provided by indices able to find the record closest to a key.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::Bound;
+/// use std::ops::RangeBounds;
+///
+/// use ironsea_index::Indexed;
+/// use ironsea_index::KeyDistance;
+/// use ironsea_index::NearestIndex;
+///
+/// // Readings sorted by timestamp.
+/// struct Index(Vec<(i64, f64)>);
+///
+/// # impl Indexed<(i64, f64), i64> for Index {
+/// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, f64)> + 'i> {
+/// #        let key = *key;
+/// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+/// #    }
+/// #
+/// #    fn find_bounds<'i>(
+/// #        &'i self,
+/// #        start: Bound<&i64>,
+/// #        end: Bound<&i64>,
+/// #    ) -> Box<dyn Iterator<Item = &'i (i64, f64)> + 'i> {
+/// #        let range = (start.cloned(), end.cloned());
+/// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+/// #    }
+/// # }
+/// #
+/// impl NearestIndex<(i64, f64), i64> for Index {
+///     fn find_nearest(&self, key: &i64) -> Option<&(i64, f64)> {
+///         let position = self.0.partition_point(|r| r.0 < *key);
+///         let below = position.checked_sub(1).and_then(|p| self.0.get(p));
+///         let above = self.0.get(position);
+///
+///         match (below, above) {
+///             (Some(b), Some(a)) if a.0.distance(key) < b.0.distance(key) => Some(a),
+///             (Some(b), _) => Some(b),
+///             (None, a) => a,
+///         }
+///     }
+/// }
+///
+/// let index = Index(vec![(10, 1.0), (20, 2.0), (30, 3.0)]);
+///
+/// assert_eq!(index.find_nearest(&20), Some(&(20, 2.0)));
+/// assert_eq!(index.find_nearest(&23), Some(&(20, 2.0)));
+/// assert_eq!(index.find_nearest(&27), Some(&(30, 3.0)));
+/// assert_eq!(index.find_nearest(&-5), Some(&(10, 1.0)));
+///
+/// // Ties return the record with the lower key.
+/// assert_eq!(index.find_nearest(&25), Some(&(20, 2.0)));
+///
+/// assert_eq!(Index(vec![]).find_nearest(&25), None);
+/// ```
+pub trait NearestIndex<R, K>: Indexed<R, K>
+where
+    K: Ord + KeyDistance,
+{
+    /// Retrieve the record whose key is the closest to `key`, or `None`
+    /// if the index is empty.
+    ///
+    /// When two keys are at the same distance from `key`, a record of
+    /// the lower key is returned.
+    fn find_nearest(&self, key: &K) -> Option<&R>;
+}
+
+/// Size of an index.
+///
+/// This is kept separate from [`Indexed`], so that indices which cannot
+/// cheaply count their records, for example lazy or streaming ones, are
+/// not required to implement it.
+///
+/// The size is the number of records, not the number of distinct keys;
+/// the latter is provided by [`KeyEnumerable::distinct_key_count`].
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::KeyEnumerable;
+/// use ironsea_index::Record;
+/// use ironsea_index::SizedIndex;
+///
+/// struct MyPair {
+///     a: i64,
+///     b: i64,
+/// }
+///
+/// impl Record<i64> for MyPair {
+///     fn key(&self) -> i64 {
+///         self.a
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new((0..6).map(|b| MyPair { a: b % 2, b }).collect());
+///
+/// assert_eq!(index.len(), 6);
+/// assert!(!index.is_empty());
+///
+/// // Only two distinct keys, 0 and 1.
+/// assert_eq!(index.distinct_key_count(), 2);
+///
+/// assert!(SortedVecIndex::<MyPair, i64>::new(vec![]).is_empty());
+/// ```
+pub trait SizedIndex {
+    /// Number of records stored in the index.
+    fn len(&self) -> usize;
+
+    /// Check whether the index contains no record.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Estimation of the memory used by an index, for capacity planning.
+///
+/// The estimate accounts for the memory allocated by the index itself,
+/// including the unused capacity of its buffers, from the inline size of
+/// the keys and records. Memory owned by the records or the keys, for
+/// example the contents of a `String` field, is not accounted for: when
+/// it matters, the footprint of the records has to be added separately.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::IndexedCollection;
+/// use ironsea_index::MemoryFootprint;
+/// use ironsea_index::Record;
+///
+/// struct Point(u64, u64);
+///
+/// impl Record<u64> for Point {
+///     fn key(&self) -> u64 {
+///         self.0
+///     }
+/// }
+///
+/// let mut index = SortedVecIndex::new(vec![]);
+/// let empty = index.heap_bytes();
+///
+/// for i in 0..1000 {
+///     index.insert(Point(i, i));
+/// }
+/// let full = index.heap_bytes();
+/// assert!(full >= empty + 1000 * (8 + 16));
+///
+/// index.retain(|p| p.0 < 10);
+/// assert_eq!(index.heap_bytes(), full);
+/// index.shrink_to_fit();
+/// assert!(index.heap_bytes() < full);
+/// ```
+pub trait MemoryFootprint {
+    /// Approximate number of bytes allocated on the heap by the index.
+    fn heap_bytes(&self) -> usize;
+}
+
+/// Methods provided by indices able to enumerate their keys.
+///
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::KeyEnumerable;
+///
+/// struct Index(Vec<(i64, u32)>);
+///
+/// impl KeyEnumerable<i64> for Index {
+///     fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i i64> + 'i> {
+///         Box::new(self.0.iter().map(|r| &r.0))
+///     }
+/// }
+///
+/// let index = Index(vec![(3, 30), (1, 10), (3, 31), (2, 20), (1, 11)]);
+///
+/// assert_eq!(index.keys().collect::<Vec<_>>(), vec![&3, &1, &3, &2, &1]);
+/// assert_eq!(index.distinct_keys().collect::<Vec<_>>(), vec![&1, &2, &3]);
+/// ```
+pub trait KeyEnumerable<K> {
+    /// Enumerate the keys of the index.
+    ///
+    /// A key is returned once for every record stored under it, so keys
+    /// shared by multiple records are returned multiple times.
+    fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i K> + 'i>;
+
+    /// Enumerate the distinct keys of the index, each key being returned
+    /// exactly once.
+    ///
+    /// The default implementation collects [`keys`](KeyEnumerable::keys)
+    /// into a `BTreeSet`, and therefore returns the keys in ascending
+    /// order. Implementations which store each key only once should
+    /// override it.
+    fn distinct_keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i K> + 'i>
+    where
+        K: Ord,
+    {
+        Box::new(self.keys().collect::<BTreeSet<_>>().into_iter())
+    }
+
+    /// Count the distinct keys of the index.
+    ///
+    /// This is useful to estimate the selectivity of a key, for example
+    /// when planning joins.
+    ///
+    /// The default implementation counts the keys returned by
+    /// [`distinct_keys`](KeyEnumerable::distinct_keys), which by default
+    /// collects all the keys in a `BTreeSet`. Implementations which can
+    /// track this number are encouraged to override it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::KeyEnumerable;
+    ///
+    /// struct Index(Vec<i64>);
+    ///
+    /// impl KeyEnumerable<i64> for Index {
+    ///     fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i i64> + 'i> {
+    ///         Box::new(self.0.iter())
+    ///     }
+    /// }
+    ///
+    /// let index = Index(vec![1, 2, 1, 3, 3, 1, 2]);
+    /// assert_eq!(index.distinct_key_count(), 3);
+    /// ```
+    fn distinct_key_count(&self) -> usize
+    where
+        K: Ord,
+    {
+        self.distinct_keys().count()
+    }
+
+    /// Count the distinct keys in the range defined by `start` and
+    /// `end`, both included.
+    ///
+    /// Unlike [`Indexed::count_range`], which counts records, each key is
+    /// counted once, however many records are stored under it. This is
+    /// useful to estimate the cardinality of a `GROUP BY` on a range.
+    ///
+    /// The default implementation collects the keys of the range into a
+    /// `BTreeSet`. Implementations keeping their keys sorted can count
+    /// the transitions between adjacent distinct keys instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::KeyEnumerable;
+    /// use ironsea_index::Record;
+    ///
+    /// struct Sale(u32);
+    ///
+    /// impl Record<u32> for Sale {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let sales = [1, 3, 3, 3, 3, 3, 4, 4, 7].iter().map(|&k| Sale(k));
+    /// let index = SortedVecIndex::new(sales.collect());
+    ///
+    /// assert_eq!(index.count_range(&3, &3), 5);
+    /// assert_eq!(index.distinct_keys_in_range(&3, &3), 1);
+    ///
+    /// assert_eq!(index.count_range(&2, &7), 8);
+    /// assert_eq!(index.distinct_keys_in_range(&2, &7), 3);
+    /// ```
+    fn distinct_keys_in_range(&self, start: &K, end: &K) -> usize
+    where
+        K: Ord,
+    {
+        self.keys()
+            .filter(|k| start <= *k && *k <= end)
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+}
+
+/// Methods provided by indices able to summarise the distribution of
+/// their keys.
+///
+/// These summaries are meant for cost-based query planning, for example
+/// to estimate the selectivity of a range query, by summing the counts
+/// of the buckets it overlaps, without running it.
+///
+///  * `K`: Type of the keys
+pub trait Statistics<K>: KeyEnumerable<K> {
+    /// Split the keys of the index in at most `buckets` buckets holding
+    /// roughly the same number of records.
+    ///
+    /// Each bucket is returned as `(low, high, count)`, where `low` and
+    /// `high` are the smallest and largest keys of the bucket, both
+    /// included, and `count` the number of records stored under these
+    /// keys. Buckets are returned in ascending key order and cover all
+    /// the records of the index.
+    ///
+    /// A key is never split across buckets, so fewer buckets are
+    /// returned when there are fewer distinct keys than requested, or
+    /// when some keys hold a large share of the records. An empty index,
+    /// or asking for zero buckets, returns no bucket.
+    ///
+    /// The default implementation counts the records of each key in a
+    /// `BTreeMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Record;
+    /// use ironsea_index::Statistics;
+    ///
+    /// struct Sample(u32);
+    ///
+    /// impl Record<u32> for Sample {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let samples = [5, 1, 2, 1, 3, 1, 4, 1].iter().map(|&v| Sample(v));
+    /// let index = SortedVecIndex::new(samples.collect());
+    ///
+    /// assert_eq!(index.key_histogram(2), vec![(1, 1, 4), (2, 5, 4)]);
+    /// assert_eq!(
+    ///     index.key_histogram(4),
+    ///     vec![(1, 1, 4), (2, 3, 2), (4, 4, 1), (5, 5, 1)]
+    /// );
+    ///
+    /// // Only 5 distinct keys.
+    /// assert_eq!(index.key_histogram(10).len(), 5);
+    ///
+    /// let empty = SortedVecIndex::<Sample, u32>::new(vec![]);
+    /// assert_eq!(empty.key_histogram(4), vec![]);
+    /// ```
+    fn key_histogram(&self, buckets: usize) -> Vec<(K, K, usize)>
+    where
+        K: Ord + Clone,
+    {
+        let mut counts = BTreeMap::new();
+        for key in self.keys() {
+            *counts.entry(key).or_insert(0) += 1;
+        }
+
+        let total = counts.values().sum();
+        equi_depth_buckets(counts.into_iter(), total, buckets)
+    }
+
+    /// Estimate the number of records matching in the key range defined
+    /// by `start` and `end`, both included.
+    ///
+    /// The estimation is best-effort: no bound on its error is
+    /// guaranteed, unless the implementation specifies one.
+    ///
+    /// The default implementation counts the matching keys returned by
+    /// [`KeyEnumerable::keys`], and is therefore exact, but in O(n).
+    /// Implementations keeping sketches or samples of their keys are
+    /// encouraged to override it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::KeyEnumerable;
+    /// use ironsea_index::Statistics;
+    ///
+    /// struct Index(Vec<u32>);
+    ///
+    /// impl KeyEnumerable<u32> for Index {
+    ///     fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+    ///         Box::new(self.0.iter())
+    ///     }
+    /// }
+    ///
+    /// impl Statistics<u32> for Index {}
+    ///
+    /// let index = Index((0..1000).collect());
+    /// assert_eq!(index.estimate_range_count(&100, &199), 100);
+    ///
+    /// // Index estimating from one key out of ten, so the estimation is
+    /// // only a multiple of 10.
+    /// struct Sampled(Vec<u32>);
+    ///
+    /// impl KeyEnumerable<u32> for Sampled {
+    ///     fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+    ///         Box::new(self.0.iter())
+    ///     }
+    /// }
+    ///
+    /// impl Statistics<u32> for Sampled {
+    ///     fn estimate_range_count(&self, start: &u32, end: &u32) -> usize {
+    ///         let sample = self.0.iter().step_by(10);
+    ///         10 * sample.filter(|k| start <= *k && *k <= end).count()
+    ///     }
+    /// }
+    ///
+    /// let index = Sampled((0..1000).collect());
+    /// assert_eq!(index.estimate_range_count(&100, &199), 100);
+    /// assert_eq!(index.estimate_range_count(&100, &104), 10);
+    /// ```
+    fn estimate_range_count(&self, start: &K, end: &K) -> usize
+    where
+        K: Ord,
+    {
+        self.keys().filter(|k| start <= *k && *k <= end).count()
+    }
+}
+
+/// Group the keys into at most `buckets` buckets holding roughly the
+/// same number of records, from the number of records of each distinct
+/// key, in ascending key order, and the total number of records.
+pub(crate) fn equi_depth_buckets<'k, K, I>(
+    counts: I,
+    total: usize,
+    buckets: usize,
+) -> Vec<(K, K, usize)>
+where
+    K: Clone + 'k,
+    I: Iterator<Item = (&'k K, usize)>,
+{
+    let mut remaining = total;
+    let mut histogram: Vec<(K, K, usize)> = Vec::new();
+    if buckets == 0 {
+        return histogram;
+    }
+
+    // Number of records the current bucket should reach before being
+    // closed, recomputed for every bucket to spread the rounding errors.
+    let target = |remaining: usize, closed: usize| {
+        let left = (buckets - closed).max(1);
+        remaining.div_ceil(left)
+    };
+    let mut open = false;
+    let mut goal = target(remaining, 0);
+
+    for (key, count) in counts {
+        match histogram.last_mut() {
+            Some((_, high, filled)) if open => {
+                *high = key.clone();
+                *filled += count;
+            }
+            _ => {
+                histogram.push((key.clone(), key.clone(), count));
+                open = true;
+            }
+        }
+
+        let (_, _, filled) = histogram.last().unwrap();
+        if *filled >= goal {
+            remaining -= filled;
+            open = false;
+            goal = target(remaining, histogram.len());
+        }
+    }
+
+    histogram
+}
+
+/// Probabilistic membership test on the keys of an index.
+///
+/// This is used to skip lookups of keys which are definitely absent,
+/// without touching the index itself, for example with a bloom filter.
+///
+///  * `K`: Type of the keys
+pub trait MembershipFilter<K> {
+    /// Check whether `key` might be stored in the index.
+    ///
+    /// False positives are allowed: `true` may be returned for a key
+    /// which is absent. False negatives are not: `false` must only be
+    /// returned for keys which are definitely absent.
+    fn might_contain(&self, key: &K) -> bool;
+}
+
+/// Hint that a key range is about to be queried.
+///
+/// Indices whose records are not kept in memory, for example
+/// disk-backed or memory-mapped ones, can use this to warm up their
+/// caches ahead of the actual queries, so that query planners can
+/// announce their upcoming access patterns.
+///
+/// The hint is advisory: implementations are free to ignore it, and the
+/// results of the queries are the same whether it is given or not. The
+/// default implementation does nothing, which is what the in-memory
+/// indices of this crate do.
+///
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+///
+/// use ironsea_index::Prefetch;
+///
+/// // Index recording the prefetched ranges, in place of loading pages.
+/// #[derive(Default)]
+/// struct Index {
+///     prefetched: RefCell<Vec<(u64, u64)>>,
+/// }
+///
+/// impl Prefetch<u64> for Index {
+///     fn prefetch_range(&self, start: &u64, end: &u64) {
+///         self.prefetched.borrow_mut().push((*start, *end));
+///     }
+/// }
+///
+/// let index = Index::default();
+/// index.prefetch_range(&100, &200);
+/// assert_eq!(*index.prefetched.borrow(), vec![(100, 200)]);
+/// ```
+pub trait Prefetch<K> {
+    /// Hint that records in the key range defined by `start` and `end`,
+    /// both included, are about to be retrieved.
+    fn prefetch_range(&self, _start: &K, _end: &K) {}
+}
+
+/// Methods provided by indices allowing in-place modification of their
+/// records.
+///
+/// **Warning:** the records returned by these methods must not have the
+/// fields participating in their key modified, as this would break the
+/// invariants of the index, for example its ordering. If such a change
+/// is required, call [`reindex`](IndexedMut::reindex) once done.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::Bound;
+/// use std::ops::RangeBounds;
+///
+/// use ironsea_index::Indexed;
+/// use ironsea_index::IndexedMut;
+///
+/// struct Index(Vec<(i64, u32)>);
+///
+/// # impl Indexed<(i64, u32), i64> for Index {
+/// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+/// #        let key = *key;
+/// #        Box::new(self.0.iter().filter(move |r| r.0 == key))
+/// #    }
+/// #
+/// #    fn find_bounds<'i>(
+/// #        &'i self,
+/// #        start: Bound<&i64>,
+/// #        end: Bound<&i64>,
+/// #    ) -> Box<dyn Iterator<Item = &'i (i64, u32)> + 'i> {
+/// #        let range = (start.cloned(), end.cloned());
+/// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.0)))
+/// #    }
+/// # }
+/// #
+/// impl IndexedMut<(i64, u32), i64> for Index {
+///     fn find_mut<'i>(
+///         &'i mut self,
+///         key: &i64,
+///     ) -> Box<dyn Iterator<Item = &'i mut (i64, u32)> + 'i> {
+///         let key = *key;
+///         Box::new(self.0.iter_mut().filter(move |r| r.0 == key))
+///     }
+///
+///     fn find_range_mut<'i>(
+///         &'i mut self,
+///         start: &i64,
+///         end: &i64,
+///     ) -> Box<dyn Iterator<Item = &'i mut (i64, u32)> + 'i> {
+///         let (start, end) = (*start, *end);
+///         Box::new(self.0.iter_mut().filter(move |r| start <= r.0 && r.0 <= end))
+///     }
+///
+///     fn reindex(&mut self) {
+///         self.0.sort_by_key(|r| r.0);
+///     }
+/// }
+///
+/// let mut index = Index(vec![(1, 10), (2, 20), (3, 30)]);
+///
+/// // Only update the non-key field.
+/// for record in index.find_mut(&2) {
+///     record.1 += 1;
+/// }
+/// assert_eq!(index.find(&2).collect::<Vec<_>>(), vec![&(2, 21)]);
+///
+/// for record in index.find_range_mut(&1, &2) {
+///     record.1 = 0;
+/// }
+/// assert_eq!(
+///     index.find_range(&1, &3).collect::<Vec<_>>(),
+///     vec![&(1, 0), &(2, 0), &(3, 30)]
+/// );
+/// ```
+pub trait IndexedMut<R, K>: Indexed<R, K> {
+    /// Retrieve mutable references to all records matching the key.
+    fn find_mut<'i>(&'i mut self, key: &K) -> Box<dyn Iterator<Item = &'i mut R> + 'i>;
+
+    /// Retrieve mutable references to all records matching in the key
+    /// range defined by `start` and `end`, both included.
+    fn find_range_mut<'i>(
+        &'i mut self,
+        start: &K,
+        end: &K,
+    ) -> Box<dyn Iterator<Item = &'i mut R> + 'i>;
+
+    /// Restore the invariants of the index after fields participating
+    /// in the key of some records have been modified.
+    fn reindex(&mut self);
+}
+
+/// Methods provided by indices which can be modified after they have
+/// been built.
+///
+/// The key of each record is extracted with its [`Record`]
+/// implementation.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::Bound;
+/// use std::ops::RangeBounds;
+///
+/// use ironsea_index::Indexed;
+/// use ironsea_index::IndexedCollection;
+/// use ironsea_index::Record;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct MyPair {
+///     a: i64,
+///     b: i64,
+/// }
+///
+/// impl Record<i64> for MyPair {
+///     fn key(&self) -> i64 {
+///         self.a
+///     }
+/// }
+///
+/// #[derive(Default)]
+/// struct Index(Vec<MyPair>);
+///
+/// # impl Indexed<MyPair, i64> for Index {
+/// #    fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i MyPair> + 'i> {
+/// #        let key = *key;
+/// #        Box::new(self.0.iter().filter(move |r| r.key() == key))
+/// #    }
+/// #
+/// #    fn find_bounds<'i>(
+/// #        &'i self,
+/// #        start: Bound<&i64>,
+/// #        end: Bound<&i64>,
+/// #    ) -> Box<dyn Iterator<Item = &'i MyPair> + 'i> {
+/// #        let range = (start.cloned(), end.cloned());
+/// #        Box::new(self.0.iter().filter(move |r| range.contains(&r.key())))
+/// #    }
+/// # }
+/// #
+/// impl IndexedCollection<MyPair, i64> for Index {
+///     fn insert(&mut self, record: MyPair) {
+///         // Insert after the last record with a smaller or equal key.
+///         let key = record.key();
+///         let position = self.0.partition_point(|r| r.key() <= key);
+///         self.0.insert(position, record);
+///     }
+///
+///     fn remove(&mut self, key: &i64) -> Vec<MyPair> {
+///         let (removed, kept) = self.0.drain(..).partition(|r| r.key() == *key);
+///         self.0 = kept;
+///         removed
+///     }
+///
+///     fn retain<P: Fn(&MyPair) -> bool>(&mut self, predicate: P) {
+///         self.0.retain(predicate);
+///     }
+/// }
+///
+/// let mut index = Index::default();
+/// index.insert(MyPair { a: 2, b: 20 });
+/// index.insert(MyPair { a: 1, b: 10 });
+/// index.insert(MyPair { a: 2, b: 21 });
+///
+/// // Duplicate keys are kept in insertion order.
+/// assert_eq!(
+///     index.find(&2).collect::<Vec<_>>(),
+///     vec![&MyPair { a: 2, b: 20 }, &MyPair { a: 2, b: 21 }]
+/// );
+///
+/// assert_eq!(index.remove(&1), vec![MyPair { a: 1, b: 10 }]);
+/// assert_eq!(index.remove(&1), vec![]);
+/// assert_eq!(index.count_range(&0, &10), 2);
+///
+/// index.retain(|r| r.b > 20);
+/// assert_eq!(index.find(&2).collect::<Vec<_>>(), vec![&MyPair { a: 2, b: 21 }]);
+/// ```
+pub trait IndexedCollection<R, K>: Indexed<R, K>
+where
+    R: Record<K>,
+{
+    /// Insert a record in the index, under the key returned by
+    /// [`Record::key`].
+    ///
+    /// When records are already stored under the same key, the new
+    /// record is appended after them, so that records sharing a key are
+    /// kept in insertion order.
+    fn insert(&mut self, record: R);
+
+    /// Remove all the records matching the key, and return them in the
+    /// order they were stored.
+    ///
+    /// Removing a key which is not present in the index returns an
+    /// empty vector.
+    fn remove(&mut self, key: &K) -> Vec<R>;
+
+    /// Remove all the records for which `predicate` returns `false`.
+    ///
+    /// The remaining records keep their order, so the invariants of the
+    /// index still hold, without rebuilding it. For indices storing
+    /// their records in an array, this is in O(n).
+    fn retain<P: Fn(&R) -> bool>(&mut self, predicate: P);
+
+    /// Remove all the records matching in the key range defined by
+    /// `start` and `end`, both included, and return them in key order.
+    ///
+    /// An empty range removes nothing and returns an empty vector.
+    ///
+    /// The default implementation [`remove`](IndexedCollection::remove)s
+    /// each key found in the range, implementations storing their
+    /// records in an array are encouraged to override it to remove the
+    /// whole range at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::IndexedCollection;
+    /// use ironsea_index::Record;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Item(u32, char);
+    ///
+    /// impl Record<u32> for Item {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let mut index = SortedVecIndex::new(
+    ///     "abcdefg".chars().enumerate().map(|(k, c)| Item(k as u32 / 2, c)).collect(),
+    /// );
+    ///
+    /// let drained = index.drain_range(&1, &2);
+    /// assert_eq!(drained.iter().map(|r| r.1).collect::<String>(), "cdef");
+    /// assert_eq!(
+    ///     index.find_range_bounds(..).map(|r| r.1).collect::<String>(),
+    ///     "abg"
+    /// );
+    ///
+    /// assert_eq!(index.drain_range(&2, &1), vec![]);
+    /// assert_eq!(index.drain_range(&1, &2), vec![]);
+    /// ```
+    fn drain_range(&mut self, start: &K, end: &K) -> Vec<R>
+    where
+        K: PartialEq,
+    {
+        let mut keys = self
+            .find_range(start, end)
+            .map(Record::key)
+            .collect::<Vec<_>>();
+        keys.dedup();
+
+        keys.iter().flat_map(|key| self.remove(key)).collect()
+    }
+
+    /// Replace the first record stored under `old_key` by `record`,
+    /// moving it under the key returned by [`Record::key`].
+    ///
+    /// This is [`update_key_by`](IndexedCollection::update_key_by)
+    /// accepting any of the records of `old_key`, meant for keys held by
+    /// a single record. When several records share `old_key`, the first
+    /// one returned by [`find`](Indexed::find) is replaced, which may not
+    /// be the previous version of `record`: use `update_key_by` to pick
+    /// it instead.
+    ///
+    /// Returns `false`, and drops `record` without inserting it, when no
+    /// record is stored under `old_key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::IndexedCollection;
+    /// use ironsea_index::Record;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct User {
+    ///     email: &'static str,
+    ///     id: u32,
+    /// }
+    ///
+    /// impl Record<&'static str> for User {
+    ///     fn key(&self) -> &'static str {
+    ///         self.email
+    ///     }
+    /// }
+    ///
+    /// let mut index = SortedVecIndex::new(vec![
+    ///     User { email: "a@example.com", id: 1 },
+    ///     User { email: "b@example.com", id: 2 },
+    /// ]);
+    ///
+    /// assert!(index.update_key(&"a@example.com", User { email: "c@example.com", id: 1 }));
+    /// assert!(!index.contains_key(&"a@example.com"));
+    /// assert_eq!(index.find(&"c@example.com").map(|u| u.id).collect::<Vec<_>>(), vec![1]);
+    ///
+    /// assert!(!index.update_key(&"a@example.com", User { email: "d@example.com", id: 1 }));
+    /// assert_eq!(index.count_range(&"a", &"z"), 2);
+    /// ```
+    fn update_key(&mut self, old_key: &K, record: R) -> bool {
+        self.update_key_by(old_key, record, |_| true)
+    }
+
+    /// Replace the first record stored under `old_key` for which
+    /// `is_same` returns `true` by `record`, moving it under the key
+    /// returned by [`Record::key`].
+    ///
+    /// This is how a record whose key changed is updated: as the index
+    /// is ordered by the previous key, modifying it in place, for example
+    /// through [`IndexedMut::find_mut`], would break the invariants of
+    /// the index. As several records may share `old_key`, `is_same`
+    /// identifies the previous version of `record` among them, usually
+    /// by comparing a field which did not change. That version is
+    /// removed from under `old_key`, the other records sharing that key
+    /// keeping their order, and `record` is inserted as by
+    /// [`insert`](IndexedCollection::insert), after the records already
+    /// stored under its key.
+    ///
+    /// Returns `false`, and drops `record` without inserting it, when no
+    /// record stored under `old_key` matches `is_same`.
+    ///
+    /// The default implementation [`remove`](IndexedCollection::remove)s
+    /// all the records of `old_key` and inserts back the others one by
+    /// one, so for `n` records under `old_key` it performs `n`
+    /// insertions, counting the one of `record`. Implementations are
+    /// encouraged to override it to only remove the moved record, as the
+    /// indices provided by this crate do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::Indexed;
+    /// use ironsea_index::IndexedCollection;
+    /// use ironsea_index::Record;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Ticket {
+    ///     status: u8,
+    ///     id: u32,
+    /// }
+    ///
+    /// impl Record<u8> for Ticket {
+    ///     fn key(&self) -> u8 {
+    ///         self.status
+    ///     }
+    /// }
+    ///
+    /// let mut index = SortedVecIndex::new(vec![
+    ///     Ticket { status: 0, id: 1 },
+    ///     Ticket { status: 0, id: 2 },
+    ///     Ticket { status: 0, id: 3 },
+    ///     Ticket { status: 1, id: 4 },
+    /// ]);
+    ///
+    /// // Move the second ticket of status 0.
+    /// assert!(index.update_key_by(&0, Ticket { status: 1, id: 2 }, |t| t.id == 2));
+    ///
+    /// assert_eq!(index.find(&0).map(|t| t.id).collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(index.find(&1).map(|t| t.id).collect::<Vec<_>>(), vec![4, 2]);
+    ///
+    /// // No ticket 5 under status 0.
+    /// assert!(!index.update_key_by(&0, Ticket { status: 1, id: 5 }, |t| t.id == 5));
+    /// assert!(!index.update_key_by(&2, Ticket { status: 0, id: 4 }, |t| t.id == 4));
+    /// assert_eq!(index.find(&0).map(|t| t.id).collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(index.count(&1), 2);
+    /// ```
+    fn update_key_by<F>(&mut self, old_key: &K, record: R, mut is_same: F) -> bool
+    where
+        F: FnMut(&R) -> bool,
+    {
+        let mut previous = self.remove(old_key);
+        let found = previous.iter().position(&mut is_same);
+        if let Some(position) = found {
+            previous.remove(position);
+        }
+
+        for other in previous {
+            self.insert(other);
+        }
+        if found.is_none() {
+            return false;
+        }
+        self.insert(record);
+
+        true
+    }
+}
+
+/// Indices which can be combined into one, for example to merge small
+/// batches of records into a larger index.
+///
+/// All the records of both indices are kept, including those sharing a
+/// key across both indices. For such keys, the records of `self` come
+/// before the records of `other`.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::MergeableIndex;
+/// use ironsea_index::Record;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let a = SortedVecIndex::new(vec![Item(1, 'a'), Item(3, 'b'), Item(5, 'c')]);
+/// let b = SortedVecIndex::new(vec![Item(2, 'd'), Item(4, 'e')]);
+/// let c = SortedVecIndex::new(vec![Item(3, 'f'), Item(5, 'g'), Item(6, 'h')]);
+///
+/// // Disjoint keys.
+/// let merged = a.clone().merge(b);
+/// assert_eq!(
+///     merged.find_range(&1, &5).map(|r| r.1).collect::<String>(),
+///     "adbec"
+/// );
+///
+/// // Overlapping keys.
+/// let merged = a.clone().merge(c.clone());
+/// for key in 0..7 {
+///     let expected = a.find(&key).chain(c.find(&key)).collect::<Vec<_>>();
+///     assert_eq!(merged.find(&key).collect::<Vec<_>>(), expected);
+/// }
+/// ```
+pub trait MergeableIndex: Sized {
+    /// Combine `self` and `other` into a single index, holding the
+    /// records of both.
+    fn merge(self, other: Self) -> Self;
+}
+
+/// Indices which can be split in two at a key, for example to process
+/// each part in parallel.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::MergeableIndex;
+/// use ironsea_index::Record;
+/// use ironsea_index::SizedIndex;
+/// use ironsea_index::SplittableIndex;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(
+///     "abcdef".chars().enumerate().map(|(k, c)| Item(k as u32, c)).collect(),
+/// );
+///
+/// let (left, right) = index.clone().split_at_key(&3);
+/// assert_eq!(left.find_range_bounds(..).map(|r| r.1).collect::<String>(), "abc");
+/// assert_eq!(right.find_range_bounds(..).map(|r| r.1).collect::<String>(), "def");
+/// assert_eq!(left.merge(right), index);
+///
+/// let (left, right) = index.clone().split_at_key(&0);
+/// assert_eq!((left.len(), right.len()), (0, 6));
+///
+/// let (left, right) = index.split_at_key(&9);
+/// assert_eq!((left.len(), right.len()), (6, 0));
+/// ```
+pub trait SplittableIndex<K>: Sized {
+    /// Split the index in two: the records whose key is less than
+    /// `pivot`, and those whose key is greater than or equal to it.
+    fn split_at_key(self, pivot: &K) -> (Self, Self);
+}
+
+/// Conversion of an index to the standard maps, for interoperability.
+///
+/// Each key is mapped to all its records, in the order the index returns
+/// them. Every key and record is cloned, so this is expensive on large
+/// indices.
+///
+///  * `R`: Type of the records
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Indexed;
+/// use ironsea_index::MapConvertible;
+/// use ironsea_index::Record;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let index = SortedVecIndex::new(vec![Item(2, 'a'), Item(1, 'b'), Item(2, 'c')]);
+///
+/// let map = index.to_btreemap();
+/// assert_eq!(map[&2], vec![Item(2, 'a'), Item(2, 'c')]);
+///
+/// let back = SortedVecIndex::new(map.clone().into_values().flatten().collect());
+/// for key in 0..4 {
+///     let expected = index.find(&key).collect::<Vec<_>>();
+///     assert_eq!(map.find(&key).collect::<Vec<_>>(), expected);
+///     assert_eq!(back.find(&key).collect::<Vec<_>>(), expected);
+/// }
+/// ```
+pub trait MapConvertible<R, K> {
+    /// Copy the index to a `BTreeMap`.
+    fn to_btreemap(&self) -> BTreeMap<K, Vec<R>>
+    where
+        K: Ord + Clone,
+        R: Clone;
+
+    /// Copy the index to a `HashMap`.
+    ///
+    /// This requires the `std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::impls::SortedVecIndex;
+    /// use ironsea_index::MapConvertible;
+    /// use ironsea_index::Record;
+    ///
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Item(u32, char);
+    ///
+    /// impl Record<u32> for Item {
+    ///     fn key(&self) -> u32 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let index = SortedVecIndex::new(vec![Item(2, 'a'), Item(1, 'b'), Item(2, 'c')]);
+    ///
+    /// let hash_map = index.to_hashmap();
+    /// assert_eq!(hash_map[&1], vec![Item(1, 'b')]);
+    /// assert_eq!(hash_map[&2], vec![Item(2, 'a'), Item(2, 'c')]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn to_hashmap(&self) -> HashMap<K, Vec<R>>
+    where
+        K: Hash + Eq + Clone,
+        R: Clone;
+}
+
+/// Methods provided by destructuring indices.
+///
+/// This kind of indices store inside the index both keys and values,
+/// meaning the original records can be freed.
+///
+///  * `F`: Type of the struct containing the remaining fields
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::ControlFlow;
+///
+/// use ironsea_index::IndexedDestructured;
+/// use ironsea_index::RecordBuilder;
+///
+/// struct Index(Vec<(i64, u32)>);
+///
+/// impl IndexedDestructured<u32, i64> for Index {
+///     fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+///         let key = *key;
+///         Box::new(self.0.iter().filter(move |(k, _)| *k == key).map(|(_, f)| f))
+///     }
+///
+///     fn find_range<'i>(
+///         &'i self,
+///         start: &i64,
+///         end: &i64,
+///     ) -> Box<dyn Iterator<Item = (i64, &'i u32)> + 'i> {
+///         let (start, end) = (*start, *end);
+///         Box::new(
+///             self.0
+///                 .iter()
+///                 .filter(move |(k, _)| start <= *k && *k <= end)
+///                 .map(|(k, f)| (*k, f)),
+///         )
+///     }
+/// }
+///
+/// let index = Index((0..10).map(|v| (v % 3, v as u32)).collect());
+///
+/// assert_eq!(index.count(&0), 4);
+/// assert_eq!(index.count(&3), 0);
+/// assert_eq!(index.count_range(&1, &2), 6);
+/// assert_eq!(index.count_range(&2, &1), 0);
+/// assert!(index.contains_key(&1));
+/// assert!(!index.contains_key(&3));
+///
+/// // Stop at the first record of key 2 greater than 5.
+/// let found = index.for_each_in_range(&2, &2, |(k, f)| {
+///     if *f > 5 {
+///         ControlFlow::Break((k, *f))
+///     } else {
+///         ControlFlow::Continue(())
+///     }
+/// });
+/// assert_eq!(found, ControlFlow::Break((2, 8)));
+///
+/// // Stop at the first error, after visiting the records before it.
+/// let mut visited = vec![];
+/// let result = index.try_for_each_in_range(&1, &1, |(_, f)| {
+///     if *f == 7 {
+///         return Err(*f);
+///     }
+///     visited.push(*f);
+///     Ok(())
+/// });
+/// assert_eq!(result, Err(7));
+/// assert_eq!(visited, vec![1, 4]);
+///
+/// // Rebuild the records from their key and fields.
+/// #[derive(Debug, PartialEq)]
+/// struct Measurement {
+///     t: i64,
+///     temp: u32,
+/// }
+///
+/// impl RecordBuilder<Measurement, i64, u32> for Measurement {
+///     fn build(t: i64, temp: u32) -> Measurement {
+///         Measurement { t, temp }
+///     }
+/// }
+///
+/// assert_eq!(
+///     index.find_rebuilt::<Measurement, Measurement>(&2),
+///     vec![
+///         Measurement { t: 2, temp: 2 },
+///         Measurement { t: 2, temp: 5 },
+///         Measurement { t: 2, temp: 8 },
+///     ]
+/// );
+///
+/// // The trait can be used as a trait object.
+/// let index: &dyn IndexedDestructured<u32, i64> = &index;
+/// assert_eq!(index.count_range(&0, &1), 7);
+/// ```
+pub trait IndexedDestructured<F, K> {
+    /// Retrieve all records matching the key.
+    fn find<'i>(&'i self, key: &K) -> Box<dyn Iterator<Item = &'i F> + 'i>;
+
+    /// Retrieve all records matching in the key range defined by
+    /// `start` and `end`.
+    ///
+    /// * `start` is included
+    /// * `end` is included
+    fn find_range<'i>(&'i self, start: &K, end: &K) -> Box<dyn Iterator<Item = (K, &'i F)> + 'i>;
+
+    /// Count the records matching the key.
+    ///
+    /// The default implementation walks through the result of
+    /// [`find`](IndexedDestructured::find), and is therefore in
+    /// O(matches). Implementations are encouraged to override it when
+    /// their internal structure can answer directly.
+    ///
+    /// See the [examples](IndexedDestructured#examples) of the trait.
+    fn count(&self, key: &K) -> usize {
+        self.find(key).count()
+    }
+
+    /// Count the records matching in the key range defined by `start`
+    /// and `end`.
+    ///
+    /// As for [`count`](IndexedDestructured::count), the default
+    /// implementation is in O(matches) and implementations are
+    /// encouraged to override it.
+    ///
+    /// See the [examples](IndexedDestructured#examples) of the trait.
+    fn count_range(&self, start: &K, end: &K) -> usize {
+        self.find_range(start, end).count()
+    }
+
+    /// Check whether at least one record matches the key.
+    ///
+    /// The default implementation stops at the first record returned by
+    /// [`find`](IndexedDestructured::find).
+    ///
+    /// See the [examples](IndexedDestructured#examples) of the trait.
+    fn contains_key(&self, key: &K) -> bool {
+        self.find(key).next().is_some()
+    }
+
+    /// Call `f` on all records matching in the key range defined by
+    /// `start` and `end`, both included, with their key.
+    ///
+    /// Iteration stops as soon as `f` returns `ControlFlow::Break`, whose
+    /// value is then returned.
+    ///
+    /// See the [examples](IndexedDestructured#examples) of the trait.
+    fn for_each_in_range<B, G>(&self, start: &K, end: &K, f: G) -> ControlFlow<B>
+    where
+        Self: Sized,
+        G: FnMut((K, &F)) -> ControlFlow<B>,
+    {
+        self.find_range(start, end).try_for_each(f)
+    }
+
+    /// Call `f` on all records matching in the key range defined by
+    /// `start` and `end`, both included, with their key, stopping at the
+    /// first error returned by `f`.
+    ///
+    /// As for [`Indexed::try_for_each_in_range`], on error the only
+    /// guarantee is that the records visited so far were passed to `f`.
+    ///
+    /// See the [examples](IndexedDestructured#examples) of the trait.
+    fn try_for_each_in_range<E, G>(&self, start: &K, end: &K, f: G) -> Result<(), E>
+    where
+        Self: Sized,
+        G: FnMut((K, &F)) -> Result<(), E>,
+    {
+        self.find_range(start, end).try_for_each(f)
+    }
+
+    /// Retrieve all records matching the key, rebuilt from the key and
+    /// their fields with `B`.
+    ///
+    /// The key and fields are cloned for each record.
+    ///
+    /// See the [examples](IndexedDestructured#examples) of the trait.
+    fn find_rebuilt<R, B>(&self, key: &K) -> Vec<R>
+    where
+        Self: Sized,
+        B: RecordBuilder<R, K, F>,
+        K: Clone,
+        F: Clone,
+    {
+        self.find(key)
+            .map(|fields| B::build(key.clone(), fields.clone()))
+            .collect()
+    }
+}
+
+/// Methods provided by destructuring indices keeping their keys ordered.
+///
+/// This is the counterpart of [`OrderedIndex`] for destructuring
+/// indices. As the original records are gone, the key is returned along
+/// the fields.
+///
+///  * `F`: Type of the struct containing the remaining fields
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::IndexedDestructured;
+/// use ironsea_index::OrderedIndexDestructured;
+///
+/// // Fields sorted by key.
+/// struct Index(Vec<(i64, u32)>);
+///
+/// # impl IndexedDestructured<u32, i64> for Index {
+/// #     fn find<'i>(&'i self, key: &i64) -> Box<dyn Iterator<Item = &'i u32> + 'i> {
+/// #         let key = *key;
+/// #         Box::new(self.0.iter().filter(move |(k, _)| *k == key).map(|(_, f)| f))
+/// #     }
+/// #
+/// #     fn find_range<'i>(
+/// #         &'i self,
+/// #         start: &i64,
+/// #         end: &i64,
+/// #     ) -> Box<dyn Iterator<Item = (i64, &'i u32)> + 'i> {
+/// #         let (start, end) = (*start, *end);
+/// #         Box::new(
+/// #             self.0
+/// #                 .iter()
+/// #                 .filter(move |(k, _)| start <= *k && *k <= end)
+/// #                 .map(|(k, f)| (*k, f)),
+/// #         )
+/// #     }
+/// # }
+/// #
+/// impl OrderedIndexDestructured<u32, i64> for Index {
+///     fn first(&self) -> Option<(i64, &u32)> {
+///         self.0.first().map(|(k, f)| (*k, f))
+///     }
+///
+///     fn last(&self) -> Option<(i64, &u32)> {
+///         self.0.last().map(|(k, f)| (*k, f))
+///     }
+/// }
+///
+/// let index = Index(vec![(1, 10), (3, 30), (7, 70)]);
+/// assert_eq!(index.first(), Some((1, &10)));
+/// assert_eq!(index.last(), Some((7, &70)));
+///
+/// let empty = Index(vec![]);
+/// assert_eq!(empty.first(), None);
+/// assert_eq!(empty.last(), None);
+/// ```
+pub trait OrderedIndexDestructured<F, K>: IndexedDestructured<F, K>
+where
+    K: Ord,
+{
+    /// Retrieve the smallest key and its fields, or `None` if the index
+    /// is empty.
+    ///
+    /// The key must be the smallest key for which
+    /// [`IndexedDestructured::find`] returns at least one record.
+    fn first(&self) -> Option<(K, &F)>;
+
+    /// Retrieve the largest key and its fields, or `None` if the index
+    /// is empty.
+    ///
+    /// The key must be the largest key for which
+    /// [`IndexedDestructured::find`] returns at least one record.
+    fn last(&self) -> Option<(K, &F)>;
+
+    /// Retrieve the fields of the index grouped by key, in ascending key
+    /// order.
+    ///
+    /// Each distinct key is returned once, with all its fields, in the
+    /// order returned by [`IndexedDestructured::find_range`]. A key
+    /// with a single field is returned with a one-element `Vec`.
+    ///
+    /// The default implementation gathers the whole range from
+    /// [`first`](OrderedIndexDestructured::first) to
+    /// [`last`](OrderedIndexDestructured::last) in a `BTreeMap`.
+    /// Implementations returning their ranges in key order can instead
+    /// group adjacent keys as they go.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ironsea_index::IndexedDestructured;
+    /// use ironsea_index::OrderedIndexDestructured;
+    ///
+    /// // Temperatures, by minute since midnight.
+    /// struct Index(Vec<(u32, f32)>);
+    ///
+    /// # impl IndexedDestructured<f32, u32> for Index {
+    /// #     fn find<'i>(&'i self, key: &u32) -> Box<dyn Iterator<Item = &'i f32> + 'i> {
+    /// #         let key = *key;
+    /// #         Box::new(self.0.iter().filter(move |(k, _)| *k == key).map(|(_, f)| f))
+    /// #     }
+    /// #
+    /// #     fn find_range<'i>(
+    /// #         &'i self,
+    /// #         start: &u32,
+    /// #         end: &u32,
+    /// #     ) -> Box<dyn Iterator<Item = (u32, &'i f32)> + 'i> {
+    /// #         let (start, end) = (*start, *end);
+    /// #         Box::new(
+    /// #             self.0
+    /// #                 .iter()
+    /// #                 .filter(move |(k, _)| start <= *k && *k <= end)
+    /// #                 .map(|(k, f)| (*k, f)),
+    /// #         )
+    /// #     }
+    /// # }
+    /// #
+    /// impl OrderedIndexDestructured<f32, u32> for Index {
+    ///     fn first(&self) -> Option<(u32, &f32)> {
+    ///         self.0.iter().min_by_key(|(k, _)| *k).map(|(k, f)| (*k, f))
+    ///     }
+    ///
+    ///     fn last(&self) -> Option<(u32, &f32)> {
+    ///         self.0.iter().max_by_key(|(k, _)| *k).map(|(k, f)| (*k, f))
+    ///     }
+    /// }
+    ///
+    /// let index = Index(vec![(600, 12.5), (540, 10.0), (600, 13.0), (545, 10.5), (540, 9.5)]);
+    ///
+    /// assert_eq!(
+    ///     index.group_by_key(),
+    ///     vec![
+    ///         (540, vec![&10.0, &9.5]),
+    ///         (545, vec![&10.5]),
+    ///         (600, vec![&12.5, &13.0]),
+    ///     ]
+    /// );
+    /// assert_eq!(Index(vec![]).group_by_key(), vec![]);
+    /// ```
+    fn group_by_key(&self) -> Vec<(K, Vec<&F>)> {
+        let (first, last) = match (self.first(), self.last()) {
+            (Some((first, _)), Some((last, _))) => (first, last),
+            _ => return Vec::new(),
+        };
+
+        let mut groups = BTreeMap::new();
+        for (key, fields) in self.find_range(&first, &last) {
+            groups.entry(key).or_insert_with(Vec::new).push(fields);
+        }
+
+        groups.into_iter().collect()
+    }
+}
+
+/// Lookup of the key of some fields, on destructuring indices.
+///
+/// As destructuring indices return their keys by value, the key is
+/// returned by value as well.
+///
+/// The default implementation scans the whole index, from
+/// [`first`](OrderedIndexDestructured::first) to
+/// [`last`](OrderedIndexDestructured::last), and is therefore in O(n).
+/// When equal fields are stored under different keys, the first key
+/// returned by [`IndexedDestructured::find_range`] is returned, which is
+/// the smallest one for indices returning their ranges in key order.
+///
+///  * `F`: Type of the struct containing the remaining fields
+///  * `K`: Type of the keys
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::IndexedDestructured;
+/// use ironsea_index::OrderedIndexDestructured;
+/// use ironsea_index::ReverseLookup;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Position {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// // Positions sorted by timestamp.
+/// struct Index(Vec<(u64, Position)>);
+///
+/// # impl IndexedDestructured<Position, u64> for Index {
+/// #     fn find<'i>(&'i self, key: &u64) -> Box<dyn Iterator<Item = &'i Position> + 'i> {
+/// #         let key = *key;
+/// #         Box::new(self.0.iter().filter(move |(k, _)| *k == key).map(|(_, f)| f))
+/// #     }
+/// #
+/// #     fn find_range<'i>(
+/// #         &'i self,
+/// #         start: &u64,
+/// #         end: &u64,
+/// #     ) -> Box<dyn Iterator<Item = (u64, &'i Position)> + 'i> {
+/// #         let (start, end) = (*start, *end);
+/// #         Box::new(
+/// #             self.0
+/// #                 .iter()
+/// #                 .filter(move |(k, _)| start <= *k && *k <= end)
+/// #                 .map(|(k, f)| (*k, f)),
+/// #         )
+/// #     }
+/// # }
+/// #
+/// # impl OrderedIndexDestructured<Position, u64> for Index {
+/// #     fn first(&self) -> Option<(u64, &Position)> {
+/// #         self.0.first().map(|(k, f)| (*k, f))
+/// #     }
+/// #
+/// #     fn last(&self) -> Option<(u64, &Position)> {
+/// #         self.0.last().map(|(k, f)| (*k, f))
+/// #     }
+/// # }
+/// #
+/// impl ReverseLookup<Position, u64> for Index {}
+///
+/// let index = Index(vec![
+///     (10, Position { x: 0, y: 0 }),
+///     (20, Position { x: 1, y: 2 }),
+///     (30, Position { x: 0, y: 0 }),
+/// ]);
+///
+/// assert_eq!(index.key_of(&Position { x: 1, y: 2 }), Some(20));
+/// assert_eq!(index.key_of(&Position { x: 0, y: 0 }), Some(10));
+/// assert_eq!(index.key_of(&Position { x: 5, y: 5 }), None);
+/// ```
+pub trait ReverseLookup<F, K>: OrderedIndexDestructured<F, K>
+where
+    F: PartialEq,
+    K: Ord,
+{
+    /// Retrieve a key under which `fields` are stored, or `None` if they
+    /// are not in the index.
+    fn key_of(&self, fields: &F) -> Option<K> {
+        let (first, last) = match (self.first(), self.last()) {
+            (Some((first, _)), Some((last, _))) => (first, last),
+            _ => return None,
+        };
+
+        self.find_range(&first, &last)
+            .find(|(_, f)| *f == fields)
+            .map(|(key, _)| key)
+    }
 }
@@ -0,0 +1,365 @@
+//! Property checks for implementations of the index traits.
+//!
+//! Each check exercises a relationship the traits guarantee between
+//! their methods, and panics with a description of the first violation
+//! found. As the checks only rely on the public interface, implementers
+//! can run them against their own types, over keys and ranges
+//! representative of their data, present or absent, as a reusable
+//! conformance suite. The
+//! [`impl_indexed_conformance_tests`](crate::impl_indexed_conformance_tests)
+//! macro generates the tests running them, along with checks of the
+//! behavior at the boundaries.
+//!
+//! This requires the `testing` feature.
+//!
+//! # Examples
+//!
+//! The indices provided by this crate pass all the checks:
+//!
+//! ```
+//! use std::collections::BTreeMap;
+//!
+//! use ironsea_index::impls::MultiKeyIndex;
+//! use ironsea_index::impls::SortedVecIndex;
+//! use ironsea_index::testing;
+//! use ironsea_index::Record;
+//!
+//! #[derive(Clone)]
+//! struct Item(u32, char);
+//!
+//! impl Record<u32> for Item {
+//!     fn key(&self) -> u32 {
+//!         self.0
+//!     }
+//! }
+//!
+//! let items = "abcdefghij"
+//!     .chars()
+//!     .enumerate()
+//!     .map(|(i, c)| Item(i as u32 % 4 * 2, c))
+//!     .collect::<Vec<_>>();
+//! let keys = (0..10).collect::<Vec<_>>();
+//! let ranges = vec![(0, 9), (1, 1), (2, 2), (3, 6), (6, 3), (7, 9)];
+//!
+//! let sorted = SortedVecIndex::new(items.clone());
+//! testing::check_count_find_consistency(&sorted, &keys);
+//! testing::check_count_range_consistency(&sorted, &ranges);
+//! testing::check_point_range_equivalence(&sorted, &keys);
+//! testing::check_find_range_order(&sorted, &ranges);
+//!
+//! let multi = MultiKeyIndex::new(items.clone());
+//! testing::check_count_find_consistency(&multi, &keys);
+//! testing::check_count_range_consistency(&multi, &ranges);
+//! testing::check_point_range_equivalence(&multi, &keys);
+//! testing::check_find_range_order(&multi, &ranges);
+//!
+//! let mut map = BTreeMap::<_, Vec<_>>::new();
+//! for item in items {
+//!     map.entry(item.0).or_default().push(item);
+//! }
+//! map.insert(9, vec![]);
+//! testing::check_count_find_consistency(&map, &keys);
+//! testing::check_count_range_consistency(&map, &ranges);
+//! testing::check_point_range_equivalence(&map, &keys);
+//! testing::check_find_range_order(&map, &ranges);
+//! ```
+
+use core::cmp::Ordering;
+use core::ptr;
+
+use crate::Indexed;
+use crate::Record;
+
+/// Check that [`Indexed::count`] and [`Indexed::contains_key`] agree
+/// with [`Indexed::find`], for each of `keys`.
+///
+/// # Panics
+///
+/// When `count(key)` differs from the number of records returned by
+/// `find(key)`, or when `contains_key(key)` does not tell whether there
+/// are any.
+pub fn check_count_find_consistency<I, R, K>(index: &I, keys: &[K])
+where
+    I: Indexed<R, K>,
+{
+    for (i, key) in keys.iter().enumerate() {
+        let found = index.find(key).count();
+
+        assert_eq!(
+            index.count(key),
+            found,
+            "count and find disagree for keys[{}]",
+            i
+        );
+        assert_eq!(
+            index.contains_key(key),
+            found > 0,
+            "contains_key and find disagree for keys[{}]",
+            i
+        );
+    }
+}
+
+/// Check that [`Indexed::count_range`] agrees with
+/// [`Indexed::find_range`], for each of `ranges`.
+///
+/// Each range is given as its `(start, end)` bounds, both included.
+///
+/// # Panics
+///
+/// When `count_range(start, end)` differs from the number of records
+/// returned by `find_range(start, end)`.
+pub fn check_count_range_consistency<I, R, K>(index: &I, ranges: &[(K, K)])
+where
+    I: Indexed<R, K>,
+{
+    for (i, (start, end)) in ranges.iter().enumerate() {
+        assert_eq!(
+            index.count_range(start, end),
+            index.find_range(start, end).count(),
+            "count_range and find_range disagree for ranges[{}]",
+            i
+        );
+    }
+}
+
+/// Check that a range holding a single key returns the same records as
+/// [`Indexed::find`], in the same order, for each of `keys`.
+///
+/// Records are compared by address, so the index has to return the
+/// records it stores, not copies of them.
+///
+/// # Panics
+///
+/// When `find_range(key, key)` and `find(key)` return different records,
+/// or the same records in a different order.
+pub fn check_point_range_equivalence<I, R, K>(index: &I, keys: &[K])
+where
+    I: Indexed<R, K>,
+{
+    for (i, key) in keys.iter().enumerate() {
+        let mut found = index.find(key);
+        let mut ranged = index.find_range(key, key);
+
+        loop {
+            match (found.next(), ranged.next()) {
+                (None, None) => break,
+                (Some(a), Some(b)) if ptr::eq(a, b) => (),
+                _ => panic!("find and find_range disagree for keys[{}]", i),
+            }
+        }
+    }
+}
+
+/// Check that [`Indexed::find_range`] only returns records within the
+/// range, in key order, for each of `ranges`.
+///
+/// Each range is given as its `(start, end)` bounds, both included.
+///
+/// # Panics
+///
+/// When `find_range(start, end)` returns a record whose key is outside
+/// of the range, or a record with a smaller key than the one preceding
+/// it.
+pub fn check_find_range_order<I, R, K>(index: &I, ranges: &[(K, K)])
+where
+    I: Indexed<R, K>,
+    R: Record<K>,
+    K: Ord,
+{
+    for (i, (start, end)) in ranges.iter().enumerate() {
+        let mut previous = None;
+
+        for record in index.find_range(start, end) {
+            let key = record.key();
+
+            assert!(
+                start <= &key && &key <= end,
+                "find_range returned a record outside of ranges[{}]",
+                i
+            );
+            if let Some(previous) = previous {
+                assert_ne!(
+                    key.cmp(&previous),
+                    Ordering::Less,
+                    "find_range returned records out of key order for ranges[{}]",
+                    i
+                );
+            }
+            previous = Some(key);
+        }
+    }
+}
+
+/// Generate a module of `#[test]`s checking an [`Indexed`]
+/// implementation against the properties of this module, and the
+/// behavior expected at the boundaries: missing keys, duplicate keys and
+/// empty ranges.
+///
+/// The macro takes, in this order:
+///
+///  * `mod`: the name of the module to generate,
+///  * `record`: the type of the records,
+///  * `key`: the type of the keys, which must implement `Ord` and
+///    `Clone`,
+///  * `build`: a function, or closure, building the index from a
+///    `Vec` of records,
+///  * `records`: an expression returning the sample records, as a
+///    `Vec`, which should share some of their keys,
+///  * `absent_keys`: an expression returning keys of no record, as a
+///    `Vec`, ideally between, before and after the keys of the records.
+///
+/// The records have to implement [`Record`] for the key type. The
+/// expressions are evaluated inside the generated module, which imports
+/// everything from its parent.
+///
+/// The tests query every key, and every range between two keys, of the
+/// records and the absent keys, so the samples should be kept small.
+///
+/// This requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```
+/// use ironsea_index::impl_indexed_conformance_tests;
+/// use ironsea_index::impls::SortedVecIndex;
+/// use ironsea_index::Record;
+///
+/// pub struct Item(u32, char);
+///
+/// impl Record<u32> for Item {
+///     fn key(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// impl_indexed_conformance_tests! {
+///     mod sorted_vec_index;
+///     record: Item;
+///     key: u32;
+///     build: SortedVecIndex::new;
+///     records: vec![Item(2, 'a'), Item(1, 'b'), Item(2, 'c'), Item(5, 'd')];
+///     absent_keys: vec![0, 3, 9];
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! impl_indexed_conformance_tests {
+    (
+        mod $name:ident;
+        record: $record:ty;
+        key: $key:ty;
+        build: $build:expr;
+        records: $records:expr;
+        absent_keys: $absent:expr;
+    ) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            use $crate::Indexed as _;
+            use $crate::Record as _;
+
+            fn records() -> ::std::vec::Vec<$record> {
+                $records
+            }
+
+            fn absent_keys() -> ::std::vec::Vec<$key> {
+                $absent
+            }
+
+            fn build() -> impl $crate::Indexed<$record, $key> {
+                ($build)(records())
+            }
+
+            /// Keys of the records, without duplicates.
+            fn present_keys() -> ::std::vec::Vec<$key> {
+                let mut keys = records()
+                    .iter()
+                    .map($crate::Record::<$key>::key)
+                    .collect::<::std::vec::Vec<_>>();
+                keys.sort();
+                keys.dedup();
+                keys
+            }
+
+            /// Keys of the records and absent keys, in ascending order.
+            fn all_keys() -> ::std::vec::Vec<$key> {
+                let mut keys = present_keys();
+                keys.extend(absent_keys());
+                keys.sort();
+                keys.dedup();
+                keys
+            }
+
+            /// Every range between two keys, reversed ones included.
+            fn all_ranges() -> ::std::vec::Vec<($key, $key)> {
+                let keys = all_keys();
+                keys.iter()
+                    .flat_map(|start| keys.iter().map(move |end| (start.clone(), end.clone())))
+                    .collect()
+            }
+
+            #[test]
+            fn count_find_consistency() {
+                $crate::testing::check_count_find_consistency(&build(), &all_keys());
+            }
+
+            #[test]
+            fn count_range_consistency() {
+                $crate::testing::check_count_range_consistency(&build(), &all_ranges());
+            }
+
+            #[test]
+            fn point_range_equivalence() {
+                $crate::testing::check_point_range_equivalence(&build(), &all_keys());
+            }
+
+            #[test]
+            fn find_range_order() {
+                $crate::testing::check_find_range_order(&build(), &all_ranges());
+            }
+
+            #[test]
+            fn absent_keys_have_no_records() {
+                let index = build();
+                for key in absent_keys() {
+                    assert_eq!(index.find(&key).count(), 0);
+                    assert_eq!(index.count(&key), 0);
+                    assert!(!index.contains_key(&key));
+                }
+            }
+
+            #[test]
+            fn duplicate_keys_return_all_records() {
+                let index = build();
+                let records = records();
+                for key in present_keys() {
+                    let expected = records
+                        .iter()
+                        .filter(|r| $crate::Record::<$key>::key(*r) == key)
+                        .count();
+                    assert_eq!(index.find(&key).count(), expected);
+                }
+            }
+
+            #[test]
+            fn empty_ranges_have_no_records() {
+                let index = build();
+                for (start, end) in all_ranges() {
+                    if start > end {
+                        assert_eq!(index.find_range(&start, &end).count(), 0);
+                    }
+                    if start == end {
+                        assert_eq!(index.find_range_bounds(start.clone()..end).count(), 0);
+                    }
+                }
+            }
+
+            #[test]
+            fn unbounded_range_has_all_records() {
+                assert_eq!(build().find_range_bounds(..).count(), records().len());
+            }
+        }
+    };
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "testing")]
+
+use std::collections::BTreeMap;
+
+use ironsea_index::impl_indexed_conformance_tests;
+use ironsea_index::impls::MultiKeyIndex;
+use ironsea_index::impls::SortedVecIndex;
+use ironsea_index::Record;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Item(i64, char);
+
+impl Record<i64> for Item {
+    fn key(&self) -> i64 {
+        self.0
+    }
+}
+
+fn items() -> Vec<Item> {
+    vec![
+        Item(3, 'a'),
+        Item(-2, 'b'),
+        Item(3, 'c'),
+        Item(8, 'd'),
+        Item(i64::MAX, 'e'),
+        Item(3, 'f'),
+        Item(i64::MIN, 'g'),
+    ]
+}
+
+fn absent() -> Vec<i64> {
+    vec![-10, 0, 4, 100]
+}
+
+fn btree_map(records: Vec<Item>) -> BTreeMap<i64, Vec<Item>> {
+    let mut map = BTreeMap::<_, Vec<_>>::new();
+    for record in records {
+        map.entry(record.0).or_default().push(record);
+    }
+    map
+}
+
+impl_indexed_conformance_tests! {
+    mod sorted_vec_index;
+    record: Item;
+    key: i64;
+    build: SortedVecIndex::new;
+    records: items();
+    absent_keys: absent();
+}
+
+impl_indexed_conformance_tests! {
+    mod multi_key_index;
+    record: Item;
+    key: i64;
+    build: MultiKeyIndex::new;
+    records: items();
+    absent_keys: absent();
+}
+
+impl_indexed_conformance_tests! {
+    mod btree_map_index;
+    record: Item;
+    key: i64;
+    build: btree_map;
+    records: items();
+    absent_keys: absent();
+}